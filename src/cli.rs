@@ -1,8 +1,19 @@
-use std::path::PathBuf;
+use std::{
+    collections::{BTreeMap, HashSet},
+    io::{self, IsTerminal},
+    path::{Path, PathBuf},
+    process,
+};
 
+use anyhow::Context;
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
 use clap::ArgAction;
-use requiem::{Directory, Hrid};
+use requiem::{
+    AttributeValue, Clusters, Config, CoverageReport, DeletePolicy, Details, Directory, Filter,
+    Graph, Hrid, ImpactReport, Integration, Matrix, Query, Requirement, Search, Tree,
+};
 use tracing::instrument;
+use uuid::Uuid;
 
 #[derive(Debug, clap::Parser)]
 #[command(version, about)]
@@ -53,6 +64,12 @@ impl Cli {
 
 #[derive(Debug, clap::Parser)]
 pub enum Command {
+    /// Create a requirements root with a commented configuration file
+    ///
+    /// Optionally, the files needed to publish the requirements with mdBook or
+    /// Sphinx are created as well.
+    Init(Init),
+
     /// Add a new requirement
     Add(Add),
 
@@ -61,21 +78,275 @@ pub enum Command {
     /// Links are parent-child relationships.
     Link(Link),
 
+    /// Remove a link between two requirements
+    Unlink(Unlink),
+
+    /// Change the HRID of a requirement
+    ///
+    /// The file is moved, and every reference to the old HRID is updated.
+    #[command(visible_alias = "mv")]
+    Rename(Rename),
+
+    /// Delete a requirement
+    ///
+    /// By default, requirements which have children are not deleted. Use
+    /// '--children' to choose what happens to the children.
+    Delete(Delete),
+
     /// Correct parent HRIDs
-    Clean,
+    Clean(Clean),
+
+    /// Change the number of digits in every HRID
+    ///
+    /// Every requirement file is renamed, every reference to an HRID is
+    /// updated, and the new number of digits is saved in the configuration.
+    MigrateDigits(MigrateDigits),
+
+    /// List suspect links
+    ///
+    /// A link is suspect if the parent requirement has changed since the link
+    /// was created or last reviewed.
+    Suspect,
+
+    /// Mark suspect links as reviewed
+    ///
+    /// This records the parents' current fingerprints in the child, clearing
+    /// any suspect links.
+    Review(Review),
+
+    /// List requirements, optionally filtered
+    ///
+    /// Requirements are listed in natural HRID order.
+    List(List),
+
+    /// Manage the tags on requirements
+    ///
+    /// Tags contribute to a requirement's fingerprint, so changing them makes
+    /// the links from its children suspect.
+    #[command(subcommand)]
+    Tag(TagCommand),
+
+    /// Search the bodies of requirements
+    ///
+    /// By default the pattern is a literal, matched without regard to case.
+    Search(SearchCommand),
+
+    /// Show a requirement in full, along with its links
+    Show(Show),
+
+    /// Edit a requirement in your editor
+    ///
+    /// The editor is taken from $VISUAL or $EDITOR. The requirement is checked
+    /// when the editor exits, and is only saved if it is still valid.
+    Edit(Edit),
+
+    /// List the direct children of a requirement
+    Children(Children),
+
+    /// Show every descendant of a requirement as an indented tree
+    Descendants(Descendants),
+
+    /// Show every ancestor of a requirement as an indented tree
+    Ancestors(Ancestors),
+
+    /// Report how well one kind of requirement is covered by another
+    ///
+    /// A requirement is covered if at least one of its children is of the
+    /// covering kind.
+    Coverage(Coverage),
+
+    /// Export a traceability matrix between two kinds of requirement
+    Matrix(MatrixCommand),
+
+    /// Export the graph of requirements in Graphviz DOT or Mermaid syntax
+    Graph(GraphCommand),
+
+    /// List the requirements which need review after a change
+    ///
+    /// Every descendant of a changed requirement is listed, along with the
+    /// chain of links by which it is reached.
+    Impact(Impact),
+
+    /// Check the requirements for problems
+    ///
+    /// Exits with a non-zero status if any problems are found.
+    Check,
 }
 
 impl Command {
     fn run(self, root: PathBuf) -> anyhow::Result<()> {
         match self {
+            Self::Init(command) => command.run(root)?,
             Self::Add(command) => command.run(root)?,
             Self::Link(command) => command.run(root)?,
-            Self::Clean => Clean::run(root)?,
+            Self::Unlink(command) => command.run(root)?,
+            Self::Rename(command) => command.run(root)?,
+            Self::Delete(command) => command.run(root)?,
+            Self::Clean(command) => command.run(root)?,
+            Self::MigrateDigits(command) => command.run(root)?,
+            Self::Suspect => Suspect::run(root)?,
+            Self::Review(command) => command.run(root)?,
+            Self::List(command) => command.run(root)?,
+            Self::Tag(command) => command.run(root)?,
+            Self::Search(command) => command.run(root)?,
+            Self::Show(command) => command.run(root)?,
+            Self::Edit(command) => command.run(root)?,
+            Self::Children(command) => command.run(root)?,
+            Self::Descendants(command) => command.run(root)?,
+            Self::Ancestors(command) => command.run(root)?,
+            Self::Coverage(command) => command.run(root)?,
+            Self::Matrix(command) => command.run(root)?,
+            Self::Graph(command) => command.run(root)?,
+            Self::Impact(command) => command.run(root)?,
+            Self::Check => Check::run(root)?,
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct Init {
+    /// The kinds of requirements that are allowed, eg. 'USR,SYS,TST'
+    ///
+    /// If none are given, all kinds are allowed.
+    #[arg(long, value_delimiter = ',', value_parser = parse_kind)]
+    kinds: Vec<String>,
+
+    /// The number of digits in an HRID
+    #[arg(long, default_value_t = Config::default().digits(), value_parser = parse_digits)]
+    digits: usize,
+
+    /// Create the files needed to publish the requirements with this tool
+    #[arg(long, value_enum)]
+    integration: Option<IntegrationArg>,
+
+    /// Prompt for each setting, using the other options as defaults
+    #[arg(short, long)]
+    interactive: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum IntegrationArg {
+    /// Publish the requirements as an mdBook
+    Mdbook,
+    /// Publish the requirements with Sphinx
+    Sphinx,
+}
+
+impl From<IntegrationArg> for Integration {
+    fn from(integration: IntegrationArg) -> Self {
+        match integration {
+            IntegrationArg::Mdbook => Self::MdBook,
+            IntegrationArg::Sphinx => Self::Sphinx,
+        }
+    }
+}
+
+impl Init {
+    #[instrument]
+    fn run(mut self, root: PathBuf) -> anyhow::Result<()> {
+        if self.interactive {
+            self.prompt()?;
+        }
+
+        let mut config = Config::default()
+            .with_allowed_kinds(self.kinds)
+            .with_digits(self.digits);
+
+        // The integrations add markdown files which aren't requirements
+        config.allow_unrecognised = self.integration.is_some();
+
+        let created =
+            Directory::new(root).init(&config, self.integration.map(Integration::from))?;
+
+        for path in created {
+            println!("Created {}", path.display());
+        }
+
+        Ok(())
+    }
+
+    /// Ask the user for each setting, until they give a valid answer.
+    fn prompt(&mut self) -> io::Result<()> {
+        loop {
+            let answer = prompt(
+                "Allowed kinds, separated by commas (blank allows any)",
+                &self.kinds.join(","),
+            )?;
+            let kinds: Result<Vec<String>, String> = answer
+                .split(',')
+                .map(str::trim)
+                .filter(|kind| !kind.is_empty())
+                .map(parse_kind)
+                .collect();
+            match kinds {
+                Ok(kinds) => {
+                    self.kinds = kinds;
+                    break;
+                }
+                Err(e) => eprintln!("{e}"),
+            }
+        }
+
+        loop {
+            let answer = prompt("Number of digits in an HRID", &self.digits.to_string())?;
+            match parse_digits(&answer) {
+                Ok(digits) => {
+                    self.digits = digits;
+                    break;
+                }
+                Err(e) => eprintln!("{e}"),
+            }
+        }
+
+        loop {
+            let default = self
+                .integration
+                .and_then(|integration| clap::ValueEnum::to_possible_value(&integration))
+                .map_or_else(|| "none".to_string(), |value| value.get_name().to_string());
+            let answer = prompt("Documentation tool (none, mdbook, sphinx)", &default)?;
+            if answer.eq_ignore_ascii_case("none") {
+                self.integration = None;
+                break;
+            }
+            match <IntegrationArg as clap::ValueEnum>::from_str(&answer, true) {
+                Ok(integration) => {
+                    self.integration = Some(integration);
+                    break;
+                }
+                Err(e) => eprintln!("{e}"),
+            }
         }
+
         Ok(())
     }
 }
 
+fn parse_kind(value: &str) -> Result<String, String> {
+    if value.is_empty() || !value.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(format!(
+            "invalid kind '{value}': kinds must be non-empty and contain only letters and digits"
+        ));
+    }
+    Ok(value.to_string())
+}
+
+fn parse_digits(value: &str) -> Result<usize, String> {
+    match value.parse() {
+        Ok(digits) if digits > 0 => Ok(digits),
+        _ => Err(format!(
+            "invalid number of digits '{value}': expected a positive integer"
+        )),
+    }
+}
+
+fn parse_attribute(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((name, value)) if !name.is_empty() => Ok((name.to_string(), value.to_string())),
+        _ => Err(format!("invalid attribute '{value}': expected NAME=VALUE")),
+    }
+}
+
 #[derive(Debug, clap::Parser)]
 pub struct Add {
     /// The kind of requirement to create.
@@ -86,21 +357,62 @@ pub struct Add {
     /// The human-readable IDs of the parent requirements.
     #[clap(long, short, value_delimiter = ',')]
     parent: Vec<Hrid>,
+
+    /// Set a custom attribute, such as 'priority=high'
+    ///
+    /// The attribute must be declared in the `attributes` of the
+    /// configuration. Lists are separated by commas. May be given more than
+    /// once.
+    #[clap(long, short, value_name = "NAME=VALUE", value_parser = parse_attribute)]
+    attribute: Vec<(String, String)>,
 }
 
 impl Add {
     #[instrument]
     fn run(self, root: PathBuf) -> anyhow::Result<()> {
         let mut directory = Directory::new(root).load_all()?;
-        let requirement = directory.add_requirement(self.kind)?;
 
-        for parent in self.parent {
+        // Check the parents before the requirement is created, so that it isn't
+        // left without its links
+        for parent in &self.parent {
+            let parent = find_requirement(directory.tree(), parent)?;
+            directory
+                .config()
+                .check_parent_kind(&self.kind, parent.hrid().kind())?;
+        }
+
+        let allowed_parent_kinds = directory
+            .config()
+            .allowed_parent_kinds(&self.kind)
+            .map(<[String]>::to_vec);
+        let mut attributes = BTreeMap::new();
+        for (name, text) in self.attribute {
+            // Undeclared attributes are reported when the schema is applied
+            let value = match directory.config().attributes().get(&name) {
+                Some(schema) => schema.parse(&name, &text)?,
+                None => AttributeValue::String(text),
+            };
+            attributes.insert(name, value);
+        }
+        let requirement = directory.add_requirement_with_attributes(self.kind, attributes)?;
+
+        for parent in &self.parent {
             // TODO: the linkage should be done before the requirement is saved by the
             // 'add_requirement' method to avoid unnecessary IO.
-            directory.link_requirement(requirement.hrid().clone(), parent)?;
+            directory.link_requirement(requirement.hrid().clone(), parent.clone())?;
         }
 
         println!("Added requirement {}", requirement.hrid());
+        if let Some(kinds) = allowed_parent_kinds {
+            if self.parent.is_empty() && !kinds.is_empty() {
+                println!(
+                    "{} may have parents of kind {}. Add one with 'req link {} <PARENT>'",
+                    requirement.hrid(),
+                    kinds.join(", "),
+                    requirement.hrid()
+                );
+            }
+        }
         Ok(())
     }
 }
@@ -112,15 +424,28 @@ pub struct Link {
 
     /// The human-readable ID of the parent document
     parent: Hrid,
+
+    /// The type of relationship between the documents, such as 'verifies'
+    ///
+    /// The relation must be declared in the `relations` of the configuration.
+    /// If the documents are already linked, the existing relation is kept
+    /// unless this is given.
+    #[clap(long = "as", value_name = "RELATION")]
+    relation: Option<String>,
 }
 
 impl Link {
     #[instrument]
     fn run(self, root: PathBuf) -> anyhow::Result<()> {
-        let directory = Directory::new(root);
-        let msg = format!("Linked {} to {}", self.child, self.parent);
+        let mut directory = Directory::new(root).load_all()?;
+        let relation = self
+            .relation
+            .as_ref()
+            .map(|relation| format!(" as {relation}"))
+            .unwrap_or_default();
+        let msg = format!("Linked {} to {}{relation}", self.child, self.parent);
 
-        directory.link_requirement(self.child, self.parent)?;
+        directory.link_requirement_as(self.child, self.parent, self.relation)?;
 
         println!("{msg}");
 
@@ -129,12 +454,1300 @@ impl Link {
 }
 
 #[derive(Debug, clap::Parser)]
-pub struct Clean {}
+pub struct Unlink {
+    /// The human-readable ID of the child document
+    child: Hrid,
+
+    /// The parent document
+    ///
+    /// This may be the parent's current HRID, the HRID recorded in the child,
+    /// or the parent's UUID. Links to deleted parents can be removed this way.
+    parent: String,
+}
+
+impl Unlink {
+    #[instrument]
+    fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        let mut directory = Directory::new(root).load_all()?;
+
+        directory.unlink_requirement(&self.child, &self.parent)?;
+
+        println!("Unlinked {} from {}", self.child, self.parent);
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct Rename {
+    /// The current human-readable ID of the requirement
+    old: Hrid,
+
+    /// The new human-readable ID
+    ///
+    /// This may change the kind or namespace, as well as the number.
+    new: Hrid,
+}
+
+impl Rename {
+    #[instrument]
+    fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        let mut directory = Directory::new(root).load_all()?;
+        let old = find_requirement(directory.tree(), &self.old)?
+            .hrid()
+            .clone();
+
+        let modified = directory.rename_requirement(&self.old, self.new.clone())?;
+
+        let new = find_requirement(directory.tree(), &self.new)?.hrid();
+        println!("Renamed {old} to {new}");
+        for hrid in modified {
+            println!("Updated {hrid}");
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct Delete {
+    /// The human-readable ID of the requirement
+    hrid: Hrid,
+
+    /// What to do with the requirement's children
+    #[arg(long, value_enum, default_value_t = ChildPolicy::Refuse)]
+    children: ChildPolicy,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum ChildPolicy {
+    /// Don't delete a requirement which has children
+    Refuse,
+    /// Remove the link from each child
+    Detach,
+    /// Link each child to the deleted requirement's parents instead
+    Reparent,
+}
+
+impl From<ChildPolicy> for DeletePolicy {
+    fn from(policy: ChildPolicy) -> Self {
+        match policy {
+            ChildPolicy::Refuse => Self::Refuse,
+            ChildPolicy::Detach => Self::Detach,
+            ChildPolicy::Reparent => Self::Reparent,
+        }
+    }
+}
+
+impl Delete {
+    #[instrument]
+    fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        let mut directory = Directory::new(root).load_all()?;
+        let path = directory.path(find_requirement(directory.tree(), &self.hrid)?.hrid());
+
+        let modified = directory.delete_requirement(&self.hrid, self.children.into())?;
+
+        for hrid in modified {
+            println!("Modified {}", directory.path(&hrid).display());
+        }
+        println!("Removed {}", path.display());
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct Clean {
+    /// Remove links to parents which no longer exist, and links from
+    /// requirements to themselves
+    #[arg(long)]
+    prune_dangling: bool,
+}
 
 impl Clean {
+    #[instrument]
+    fn run(self, path: PathBuf) -> anyhow::Result<()> {
+        let mut directory = Directory::new(path).load_all()?;
+        directory.update_hrids()?;
+
+        if self.prune_dangling {
+            for (path, link) in directory.prune_broken_links()? {
+                println!("Removed broken link from {}: {link}", path.display());
+            }
+        } else {
+            let broken_links = directory.broken_links();
+            for (path, link) in &broken_links {
+                eprintln!("{}: broken link: {link}", path.display());
+            }
+            if !broken_links.is_empty() {
+                eprintln!("Run with --prune-dangling to remove broken links");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct MigrateDigits {
+    /// The number of digits to pad the ID in each HRID to
+    #[arg(value_parser = parse_digits)]
+    digits: usize,
+}
+
+impl MigrateDigits {
+    #[instrument]
+    fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        let mut directory = Directory::new(root).load_all()?;
+
+        let renamed = directory.migrate_digits(self.digits)?;
+        for hrid in &renamed {
+            println!("Renamed {}", directory.path(hrid).display());
+        }
+        println!(
+            "Renamed {} requirement(s) to use {} digits",
+            renamed.len(),
+            self.digits
+        );
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct Suspect {}
+
+impl Suspect {
     #[instrument]
     fn run(path: PathBuf) -> anyhow::Result<()> {
-        Directory::new(path).load_all()?.update_hrids()?;
+        let directory = Directory::new(path).load_all()?;
+        let suspect_links = directory.suspect_links();
+
+        if suspect_links.is_empty() {
+            println!("No suspect links");
+            return Ok(());
+        }
+
+        for link in &suspect_links {
+            println!("{} -> {}", link.child_hrid, link.parent_hrid);
+        }
+        println!("{} suspect link(s)", suspect_links.len());
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct Review {
+    /// The human-readable ID of the child requirement
+    #[arg(required_unless_present = "all", conflicts_with = "all")]
+    child: Option<Hrid>,
+
+    /// The human-readable ID of the parent requirement
+    ///
+    /// If omitted, all the child's parent links are reviewed.
+    parent: Option<Hrid>,
+
+    /// Review every suspect link
+    #[arg(long)]
+    all: bool,
+
+    /// Only review links from children of this kind
+    #[arg(long, requires = "all")]
+    kind: Option<String>,
+
+    /// Only review links from children in this namespace
+    ///
+    /// eg. 'COMPONENT' or 'COMPONENT-SUBCOMPONENT'.
+    #[arg(long, requires = "all", value_delimiter = '-')]
+    namespace: Vec<String>,
+}
+
+impl Review {
+    #[instrument]
+    fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        let mut directory = Directory::new(root).load_all()?;
+
+        if let Some(child) = self.child {
+            let reviewed = directory.review(&child, self.parent.as_ref())?;
+            if reviewed.is_empty() {
+                println!("{child} has no suspect links");
+            }
+            for parent in reviewed {
+                println!("Reviewed {child} -> {parent}");
+            }
+        } else {
+            let reviewed = directory.review_all(self.kind.as_deref(), &self.namespace)?;
+            for link in &reviewed {
+                println!("Reviewed {} -> {}", link.child_hrid, link.parent_hrid);
+            }
+            println!("{} link(s) reviewed", reviewed.len());
+        }
+
+        Ok(())
+    }
+}
+
+/// Options for selecting requirements.
+#[derive(Debug, clap::Args)]
+struct FilterArgs {
+    /// Only include requirements of this kind (may be repeated)
+    #[arg(long)]
+    kind: Vec<String>,
+
+    /// Only include requirements in this namespace
+    ///
+    /// eg. 'COMPONENT' or 'COMPONENT-SUBCOMPONENT'.
+    #[arg(long, value_delimiter = '-')]
+    namespace: Vec<String>,
+
+    /// Only include requirements with this tag (may be repeated)
+    #[arg(long)]
+    tag: Vec<String>,
+
+    /// Only include requirements with no parents
+    #[arg(long)]
+    no_parents: bool,
+
+    /// Only include requirements with no children
+    #[arg(long)]
+    no_children: bool,
+
+    /// Only include requirements with suspect links to their parents
+    #[arg(long)]
+    suspect: bool,
+
+    /// Only include requirements created on or after this date
+    ///
+    /// Either a date (eg. '2025-07-01') or an RFC 3339 timestamp.
+    #[arg(long, value_parser = parse_datetime)]
+    created_after: Option<DateTime<Utc>>,
+
+    /// Only include requirements created before this date
+    ///
+    /// Either a date (eg. '2025-07-01') or an RFC 3339 timestamp.
+    #[arg(long, value_parser = parse_datetime)]
+    created_before: Option<DateTime<Utc>>,
+}
+
+impl From<FilterArgs> for Filter {
+    fn from(args: FilterArgs) -> Self {
+        Self {
+            kinds: args.kind,
+            namespace: args.namespace,
+            tags: args.tag,
+            no_parents: args.no_parents,
+            no_children: args.no_children,
+            suspect: args.suspect,
+            created_after: args.created_after,
+            created_before: args.created_before,
+        }
+    }
+}
+
+/// Parses a date (as midnight UTC) or an RFC 3339 timestamp.
+fn parse_datetime(value: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_time(NaiveTime::MIN).and_utc());
+    }
+    DateTime::parse_from_rfc3339(value)
+        .map(|datetime| datetime.with_timezone(&Utc))
+        .map_err(|_| format!("expected a date (YYYY-MM-DD) or RFC 3339 timestamp: {value}"))
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct List {
+    #[command(flatten)]
+    filter: FilterArgs,
+
+    /// Only include requirements matching this query
+    ///
+    /// eg. 'kind = SYS and tag:safety and not leaf'.
+    #[arg(long = "where", value_name = "QUERY")]
+    query: Option<String>,
+
+    /// The output format
+    #[arg(long, value_enum, default_value_t = ListFormat::Table)]
+    format: ListFormat,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum ListFormat {
+    /// A table of HRIDs, creation dates, link counts and tags
+    Table,
+    /// One HRID per line
+    Plain,
+    /// A JSON array
+    Json,
+}
+
+/// A requirement in the JSON output of 'req list'.
+#[derive(Debug, serde::Serialize)]
+struct ListEntry<'a> {
+    hrid: &'a Hrid,
+    uuid: Uuid,
+    created: DateTime<Utc>,
+    tags: Vec<&'a str>,
+    parents: Vec<&'a Hrid>,
+    children: Vec<&'a Hrid>,
+}
+
+impl List {
+    #[instrument]
+    fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        let directory = Directory::new(root).load_all()?;
+        let tree = directory.tree();
+        let mut requirements = Filter::from(self.filter).apply(tree);
+        if let Some(query) = &self.query {
+            let selected = select(tree, query)?;
+            requirements.retain(|requirement| selected.contains(requirement.hrid()));
+        }
+
+        let hrids = |uuids: Vec<Uuid>| -> Vec<&Hrid> {
+            sorted_requirements(tree, uuids.into_iter())
+                .into_iter()
+                .map(Requirement::hrid)
+                .collect()
+        };
+
+        match self.format {
+            ListFormat::Plain => {
+                for requirement in requirements {
+                    println!("{}", requirement.hrid());
+                }
+            }
+            ListFormat::Table => {
+                let rows: Vec<[String; 5]> = requirements
+                    .iter()
+                    .map(|requirement| {
+                        let uuid = requirement.uuid();
+                        let tags: Vec<&str> =
+                            requirement.tags().iter().map(String::as_str).collect();
+                        [
+                            requirement.hrid().to_string(),
+                            requirement.created().format("%Y-%m-%d").to_string(),
+                            tree.parents(uuid).count().to_string(),
+                            tree.children(uuid).count().to_string(),
+                            tags.join(", "),
+                        ]
+                    })
+                    .collect();
+                print_table(&["HRID", "CREATED", "PARENTS", "CHILDREN", "TAGS"], &rows);
+            }
+            ListFormat::Json => {
+                let entries: Vec<ListEntry> = requirements
+                    .iter()
+                    .map(|requirement| ListEntry {
+                        hrid: requirement.hrid(),
+                        uuid: requirement.uuid(),
+                        created: requirement.created(),
+                        tags: requirement.tags().iter().map(String::as_str).collect(),
+                        parents: hrids(tree.parents(requirement.uuid()).collect()),
+                        children: hrids(tree.children(requirement.uuid()).collect()),
+                    })
+                    .collect();
+                println!("{}", serde_json::to_string_pretty(&entries)?);
+            }
+        }
+
         Ok(())
     }
 }
+
+/// Prints rows of cells as left-aligned columns.
+fn print_table<const N: usize>(headers: &[&str; N], rows: &[[String; N]]) {
+    let mut widths = headers.map(str::len);
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let print_row = |cells: [&str; N]| {
+        let line: Vec<String> = cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{cell:width$}"))
+            .collect();
+        println!("{}", line.join("  ").trim_end());
+    };
+
+    print_row(*headers);
+    for row in rows {
+        print_row(row.each_ref().map(String::as_str));
+    }
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum TagCommand {
+    /// Add tags to requirements
+    Add(TagEdit),
+
+    /// Remove tags from requirements
+    Remove(TagEdit),
+
+    /// List the tags on a requirement, or every tag in use
+    List(TagList),
+}
+
+impl TagCommand {
+    fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        match self {
+            Self::Add(command) => command.run(root, true),
+            Self::Remove(command) => command.run(root, false),
+            Self::List(command) => command.run(root),
+        }
+    }
+}
+
+#[derive(Debug, clap::Args)]
+pub struct TagEdit {
+    /// The human-readable ID of the requirement, followed by the tags
+    ///
+    /// When using '--hrids' or '--all', every value is a tag.
+    #[arg(required = true, value_name = "HRID|TAG")]
+    values: Vec<String>,
+
+    /// Change these requirements (comma-separated)
+    #[arg(long, value_delimiter = ',', conflicts_with = "all")]
+    hrids: Vec<Hrid>,
+
+    /// Change every requirement selected by the filter options
+    #[arg(long)]
+    all: bool,
+
+    #[command(flatten)]
+    filter: FilterArgs,
+}
+
+impl TagEdit {
+    #[instrument]
+    fn run(self, root: PathBuf, add: bool) -> anyhow::Result<()> {
+        let mut directory = Directory::new(root).load_all()?;
+        let filter = Filter::from(self.filter);
+
+        let (hrids, tags) = if self.all {
+            let hrids = filter
+                .apply(directory.tree())
+                .into_iter()
+                .map(|requirement| requirement.hrid().clone())
+                .collect();
+            (hrids, self.values)
+        } else if filter != Filter::default() {
+            anyhow::bail!("filter options can only be used with '--all'");
+        } else if self.hrids.is_empty() {
+            let mut values = self.values.into_iter();
+            let Some(hrid) = values.next() else {
+                anyhow::bail!("no requirement given");
+            };
+            (vec![hrid.parse()?], values.collect())
+        } else {
+            (self.hrids, self.values)
+        };
+
+        if tags.is_empty() {
+            anyhow::bail!("no tags given");
+        }
+
+        for hrid in hrids {
+            let requirement = find_requirement(directory.tree(), &hrid)?;
+
+            let mut updated = requirement.tags().clone();
+            for tag in &tags {
+                if add {
+                    updated.insert(tag.clone());
+                } else {
+                    updated.remove(tag);
+                }
+            }
+            if updated == *requirement.tags() {
+                continue;
+            }
+
+            let suspect = directory.set_tags(&hrid, updated)?;
+            println!("Updated tags on {hrid}");
+            for child in suspect {
+                println!("  {child} -> {hrid} is now suspect");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Args)]
+pub struct TagList {
+    /// The human-readable ID of the requirement
+    ///
+    /// If omitted, every tag in use is listed with the number of requirements
+    /// which have it.
+    hrid: Option<Hrid>,
+}
+
+impl TagList {
+    #[instrument]
+    fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        let directory = Directory::new(root).load_all()?;
+        let tree = directory.tree();
+
+        if let Some(hrid) = &self.hrid {
+            for tag in find_requirement(tree, hrid)?.tags() {
+                println!("{tag}");
+            }
+            return Ok(());
+        }
+
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for requirement in tree.requirements() {
+            for tag in requirement.tags() {
+                *counts.entry(tag).or_default() += 1;
+            }
+        }
+        for (tag, count) in counts {
+            println!("{tag} ({count})");
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct SearchCommand {
+    /// The text to search for
+    pattern: String,
+
+    /// Treat the pattern as a regular expression
+    #[arg(long)]
+    regex: bool,
+
+    /// The number of lines of context to print around each matching line
+    #[arg(short = 'C', long, value_name = "N", default_value_t = 1)]
+    context: usize,
+
+    #[command(flatten)]
+    filter: FilterArgs,
+}
+
+impl SearchCommand {
+    #[instrument]
+    fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        let directory = Directory::new(root).load_all()?;
+
+        let search = if self.regex {
+            Search::regex(&self.pattern)?
+        } else {
+            Search::literal(&self.pattern)
+        }
+        .with_context(self.context);
+        let results = search.run(directory.tree(), &Filter::from(self.filter));
+
+        let highlight = io::stdout().is_terminal();
+
+        for result in &results {
+            println!("{}", result.requirement.hrid());
+            let mut previous = None;
+            for line in &result.lines {
+                // Separate lines which aren't next to each other
+                if previous.is_some_and(|previous| previous + 1 != line.number) {
+                    println!("  --");
+                }
+                previous = Some(line.number);
+
+                let mut text = String::new();
+                let mut end = 0;
+                for range in &line.matches {
+                    text.push_str(&line.text[end..range.start]);
+                    let found = &line.text[range.clone()];
+                    if highlight {
+                        text.push_str("\x1b[1;31m");
+                        text.push_str(found);
+                        text.push_str("\x1b[0m");
+                    } else {
+                        text.push_str(found);
+                    }
+                    end = range.end;
+                }
+                text.push_str(&line.text[end..]);
+
+                // Like grep, matching lines are marked with ':' and context
+                // lines with '-'
+                let separator = if line.matches.is_empty() { '-' } else { ':' };
+                println!("  {}{separator} {text}", line.number);
+            }
+        }
+
+        if results.is_empty() {
+            println!("No matches");
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct Show {
+    /// The human-readable ID of the requirement
+    hrid: Hrid,
+
+    /// The output format
+    #[arg(long, value_enum, default_value_t = ShowFormat::Text)]
+    format: ShowFormat,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum ShowFormat {
+    Text,
+    Json,
+}
+
+impl Show {
+    #[instrument]
+    fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        let directory = Directory::new(root).load_all()?;
+        let tree = directory.tree();
+        let requirement = find_requirement(tree, &self.hrid)?;
+
+        let path = directory.path(requirement.hrid());
+        let Some(details) = Details::new(tree, requirement.uuid(), path) else {
+            anyhow::bail!("requirement {} not found", self.hrid);
+        };
+
+        match self.format {
+            ShowFormat::Text => println!("{details}"),
+            ShowFormat::Json => println!("{}", serde_json::to_string_pretty(&details)?),
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct Edit {
+    /// The human-readable ID of the requirement
+    hrid: Hrid,
+}
+
+impl Edit {
+    #[instrument]
+    fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        use io::Write;
+
+        let mut directory = Directory::new(root).load_all()?;
+        let original = find_requirement(directory.tree(), &self.hrid)?.clone();
+        let hrid = original.hrid().clone();
+        let path = directory.path(&hrid);
+        let contents =
+            std::fs::read(&path).with_context(|| format!("failed to read {}", path.display()))?;
+
+        // Edit a copy, so that the requirement is never left in an invalid state
+        // The scratch file has a random name, and is deleted when dropped
+        let mut scratch = tempfile::Builder::new()
+            .prefix("req-")
+            .suffix(".md")
+            .tempfile()?;
+        scratch.write_all(&contents)?;
+        scratch.flush()?;
+        let edited = Self::edit_until_valid(scratch.path(), &hrid, &original, |edited| {
+            edited.apply_schema(directory.config().attributes())?;
+            directory.check_update(edited)?;
+            Ok(())
+        });
+        drop(scratch);
+
+        let Some((edited, bytes)) = edited? else {
+            println!("Edit abandoned; {hrid} is unchanged");
+            return Ok(());
+        };
+
+        if bytes == contents {
+            println!("No changes to {hrid}");
+            return Ok(());
+        }
+
+        let fingerprint_changed = edited.fingerprint() != original.fingerprint();
+        let suspect = directory.update_requirement(edited)?;
+        println!("Saved {hrid}");
+
+        if fingerprint_changed {
+            println!("The fingerprint of {hrid} has changed");
+            for child in suspect {
+                println!("  {child} -> {hrid} is now suspect");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Open the editor until the file holds a requirement which passes
+    /// `validate`, or the user gives up.
+    ///
+    /// Returns the parsed requirement along with the raw file contents, or
+    /// `None` if the edit was abandoned.
+    fn edit_until_valid(
+        scratch: &Path,
+        hrid: &Hrid,
+        original: &Requirement,
+        validate: impl Fn(&mut Requirement) -> anyhow::Result<()>,
+    ) -> anyhow::Result<Option<(Requirement, Vec<u8>)>> {
+        loop {
+            open_editor(scratch)?;
+
+            let bytes = std::fs::read(scratch)?;
+            let error = match Requirement::read(&mut bytes.as_slice(), hrid.clone()) {
+                Ok(edited) if edited.uuid() != original.uuid() => format!(
+                    "the UUID must not change (was {}, now {})",
+                    original.uuid(),
+                    edited.uuid()
+                ),
+                Ok(mut edited) => match validate(&mut edited) {
+                    Ok(()) => return Ok(Some((edited, bytes))),
+                    Err(e) => e.to_string(),
+                },
+                Err(e) => e.to_string(),
+            };
+
+            eprintln!("{hrid} is not valid: {error}");
+            if !confirm("Edit again? [Y/n] ")? {
+                return Ok(None);
+            }
+        }
+    }
+}
+
+/// Open a file in the user's editor, and wait for the editor to exit.
+fn open_editor(path: &Path) -> anyhow::Result<()> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+
+    // The editor may include arguments, such as 'code --wait'
+    let mut words = editor.split_whitespace();
+    let program = words.next().context("the editor command is empty")?;
+
+    let status = process::Command::new(program)
+        .args(words)
+        .arg(path)
+        .status()
+        .with_context(|| format!("failed to run editor '{editor}'"))?;
+    anyhow::ensure!(status.success(), "editor '{editor}' exited with {status}");
+
+    Ok(())
+}
+
+/// Ask the user a question on the terminal, with a default answer.
+///
+/// The default is used if the answer is blank, or at the end of input.
+fn prompt(question: &str, default: &str) -> io::Result<String> {
+    use io::Write;
+
+    eprint!("{question} [{default}]: ");
+    io::stderr().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    let answer = answer.trim();
+    Ok(if answer.is_empty() { default } else { answer }.to_string())
+}
+
+/// Ask the user a yes/no question on the terminal.
+///
+/// The default answer is yes, but the end of input is taken as no.
+fn confirm(prompt: &str) -> io::Result<bool> {
+    use io::Write;
+
+    eprint!("{prompt}");
+    io::stderr().flush()?;
+
+    let mut answer = String::new();
+    if io::stdin().read_line(&mut answer)? == 0 {
+        // End of input, so there is no one to ask
+        return Ok(false);
+    }
+    let answer = answer.trim();
+    Ok(answer.is_empty() || answer.eq_ignore_ascii_case("y") || answer.eq_ignore_ascii_case("yes"))
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct Children {
+    /// The human-readable ID of the requirement
+    hrid: Hrid,
+}
+
+impl Children {
+    #[instrument]
+    fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        let directory = Directory::new(root).load_all()?;
+        let tree = directory.tree();
+        let requirement = find_requirement(tree, &self.hrid)?;
+
+        for child in sorted_requirements(tree, tree.children(requirement.uuid())) {
+            println!("{}", child.hrid());
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct Descendants {
+    /// The human-readable ID of the requirement
+    hrid: Hrid,
+}
+
+impl Descendants {
+    #[instrument]
+    fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        let directory = Directory::new(root).load_all()?;
+        let tree = directory.tree();
+        let requirement = find_requirement(tree, &self.hrid)?;
+
+        print_tree(
+            tree,
+            requirement.uuid(),
+            &|uuid| tree.children(uuid).collect(),
+            &mut Vec::new(),
+        );
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct Ancestors {
+    /// The human-readable ID of the requirement
+    hrid: Hrid,
+}
+
+impl Ancestors {
+    #[instrument]
+    fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        let directory = Directory::new(root).load_all()?;
+        let tree = directory.tree();
+        let requirement = find_requirement(tree, &self.hrid)?;
+
+        print_tree(
+            tree,
+            requirement.uuid(),
+            &|uuid| tree.parents(uuid).collect(),
+            &mut Vec::new(),
+        );
+
+        Ok(())
+    }
+}
+
+/// Finds the HRIDs of the requirements matching a query.
+///
+/// If the query is invalid, the error shows where.
+fn select(tree: &Tree, query: &str) -> anyhow::Result<HashSet<Hrid>> {
+    let parsed = Query::parse(query).map_err(|error| {
+        anyhow::anyhow!(
+            "invalid query: {error}\n  {query}\n  {caret:>column$}",
+            caret = "^",
+            column = error.column
+        )
+    })?;
+
+    Ok(parsed
+        .evaluate(tree)
+        .into_iter()
+        .filter_map(|uuid| tree.requirement(uuid))
+        .map(|requirement| requirement.hrid().clone())
+        .collect())
+}
+
+fn find_requirement<'a>(tree: &'a Tree, hrid: &Hrid) -> anyhow::Result<&'a Requirement> {
+    tree.find_by_hrid(hrid)
+        .ok_or_else(|| anyhow::anyhow!("requirement {hrid} not found"))
+}
+
+fn sorted_requirements(tree: &Tree, uuids: impl Iterator<Item = Uuid>) -> Vec<&Requirement> {
+    let mut requirements: Vec<_> = uuids.filter_map(|uuid| tree.requirement(uuid)).collect();
+    requirements.sort_by(|a, b| a.hrid().cmp(b.hrid()));
+    requirements
+}
+
+/// Prints a requirement and its relatives as an indented tree.
+///
+/// `path` holds the requirements between the root and the current
+/// requirement. It is used to stop the recursion if the graph contains a
+/// cycle.
+fn print_tree(tree: &Tree, uuid: Uuid, next: &impl Fn(Uuid) -> Vec<Uuid>, path: &mut Vec<Uuid>) {
+    let Some(requirement) = tree.requirement(uuid) else {
+        return;
+    };
+
+    let indent = "  ".repeat(path.len());
+    if path.contains(&uuid) {
+        println!("{indent}{} (cycle)", requirement.hrid());
+        return;
+    }
+    println!("{indent}{}", requirement.hrid());
+
+    path.push(uuid);
+    for relative in sorted_requirements(tree, next(uuid).into_iter()) {
+        print_tree(tree, relative.uuid(), next, path);
+    }
+    path.pop();
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct Coverage {
+    /// The kind of requirement to report on
+    ///
+    /// If omitted, every coverage rule in the config file is reported.
+    #[arg(long, requires = "to")]
+    from: Option<String>,
+
+    /// The kind of requirement which provides the coverage
+    #[arg(long, requires = "from")]
+    to: Option<String>,
+
+    /// Only report on requirements in this namespace
+    ///
+    /// eg. 'COMPONENT' or 'COMPONENT-SUBCOMPONENT'.
+    #[arg(long, value_delimiter = '-')]
+    namespace: Vec<String>,
+
+    /// Only count links of this type of relationship, such as 'verifies'
+    #[arg(long)]
+    relation: Option<String>,
+}
+
+impl Coverage {
+    #[instrument]
+    fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        let directory = Directory::new(root).load_all()?;
+        let tree = directory.tree();
+        if let Some(relation) = &self.relation {
+            directory.config().check_relation(relation)?;
+        }
+
+        if let (Some(from), Some(to)) = (&self.from, &self.to) {
+            println!(
+                "{}",
+                CoverageReport::new(tree, from, to, &self.namespace, self.relation.as_deref())
+            );
+            return Ok(());
+        }
+
+        let rules = directory.config().coverage_rules();
+        if rules.is_empty() {
+            anyhow::bail!("no coverage rules are configured, use '--from' and '--to'");
+        }
+
+        for rule in rules {
+            let namespace = if self.namespace.is_empty() {
+                &rule.namespace
+            } else {
+                &self.namespace
+            };
+            let relation = self.relation.as_ref().or(rule.relation.as_ref());
+            println!(
+                "{}",
+                CoverageReport::new(
+                    tree,
+                    &rule.kind,
+                    &rule.covered_by,
+                    namespace,
+                    relation.map(String::as_str)
+                )
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct MatrixCommand {
+    /// The kind of requirement in the rows of the matrix
+    ///
+    /// eg. 'USR'.
+    #[arg(long)]
+    rows: String,
+
+    /// The kind of requirement which traces to the rows
+    ///
+    /// eg. 'SYS'.
+    #[arg(long)]
+    cols: String,
+
+    /// Intermediate kinds to follow links through
+    ///
+    /// eg. '--rows USR --cols TST --via SYS' traces tests to user requirements
+    /// through system requirements.
+    #[arg(long, value_delimiter = ',')]
+    via: Vec<String>,
+
+    /// Only follow links of this type of relationship, such as 'verifies'
+    #[arg(long)]
+    relation: Option<String>,
+
+    /// Only include rows for requirements matching this query
+    #[arg(long = "where", value_name = "QUERY")]
+    query: Option<String>,
+
+    /// The output format
+    #[arg(long, value_enum, default_value_t = MatrixFormat::Markdown)]
+    format: MatrixFormat,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum MatrixFormat {
+    Markdown,
+    Csv,
+    Html,
+}
+
+impl MatrixCommand {
+    #[instrument]
+    fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        let directory = Directory::new(root).load_all()?;
+        if let Some(relation) = &self.relation {
+            directory.config().check_relation(relation)?;
+        }
+        let mut matrix = Matrix::new(
+            directory.tree(),
+            &self.rows,
+            &self.cols,
+            &self.via,
+            self.relation.as_deref(),
+        );
+        if let Some(query) = &self.query {
+            let selected = select(directory.tree(), query)?;
+            matrix.retain_rows(|hrid| selected.contains(hrid));
+        }
+
+        let output = match self.format {
+            MatrixFormat::Markdown => matrix.to_markdown(),
+            MatrixFormat::Csv => matrix.to_csv(),
+            MatrixFormat::Html => matrix.to_html(),
+        };
+        print!("{output}");
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct GraphCommand {
+    /// The output format
+    #[arg(long, value_enum, default_value_t = GraphFormat::Mermaid)]
+    format: GraphFormat,
+
+    /// How to group requirements in the output
+    #[arg(long, value_enum, default_value_t = ClusterBy::None)]
+    cluster: ClusterBy,
+
+    /// Only export the subgraph around this requirement
+    #[arg(long)]
+    around: Option<Hrid>,
+
+    /// The number of levels of ancestors to include (default: all)
+    #[arg(long, requires = "around")]
+    up: Option<usize>,
+
+    /// The number of levels of descendants to include (default: all)
+    #[arg(long, requires = "around")]
+    down: Option<usize>,
+
+    /// Only include requirements matching this query
+    #[arg(long = "where", value_name = "QUERY")]
+    query: Option<String>,
+
+    /// Only include links of this type of relationship, such as 'verifies'
+    #[arg(long)]
+    relation: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum GraphFormat {
+    Dot,
+    Mermaid,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum ClusterBy {
+    None,
+    Kind,
+    Namespace,
+}
+
+impl From<ClusterBy> for Clusters {
+    fn from(cluster: ClusterBy) -> Self {
+        match cluster {
+            ClusterBy::None => Self::None,
+            ClusterBy::Kind => Self::Kind,
+            ClusterBy::Namespace => Self::Namespace,
+        }
+    }
+}
+
+impl GraphCommand {
+    #[instrument]
+    fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        let directory = Directory::new(root).load_all()?;
+        let tree = directory.tree();
+
+        let mut graph = match &self.around {
+            Some(hrid) => {
+                let requirement = find_requirement(tree, hrid)?;
+                Graph::around(tree, requirement.uuid(), self.up, self.down)
+            }
+            None => Graph::new(tree),
+        };
+        if let Some(query) = &self.query {
+            let selected = select(tree, query)?;
+            graph.retain(|hrid| selected.contains(hrid));
+        }
+        if let Some(relation) = &self.relation {
+            directory.config().check_relation(relation)?;
+            graph.retain_edges(|edge| edge.relation.as_ref() == Some(relation));
+        }
+
+        let output = match self.format {
+            GraphFormat::Dot => graph.to_dot(self.cluster.into()),
+            GraphFormat::Mermaid => graph.to_mermaid(self.cluster.into()),
+        };
+        print!("{output}");
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Parser)]
+#[command(group(
+    clap::ArgGroup::new("changes")
+        .required(true)
+        .multiple(true)
+        .args(["hrids", "files", "since"])
+))]
+pub struct Impact {
+    /// The human-readable IDs of the changed requirements
+    hrids: Vec<Hrid>,
+
+    /// Paths to changed requirement files
+    #[arg(long, value_delimiter = ',')]
+    files: Vec<PathBuf>,
+
+    /// Treat every requirement whose content or tags have changed since this
+    /// git revision as changed, including new files which git doesn't track
+    /// yet
+    #[arg(long)]
+    since: Option<String>,
+}
+
+impl Impact {
+    #[instrument]
+    fn run(self, root: PathBuf) -> anyhow::Result<()> {
+        let directory = Directory::new(root).load_all()?;
+        let tree = directory.tree();
+
+        let mut changed = Vec::new();
+
+        for hrid in &self.hrids {
+            changed.push(find_requirement(tree, hrid)?.uuid());
+        }
+
+        for path in &self.files {
+            let hrid = hrid_from_path(path)?;
+            changed.push(find_requirement(tree, &hrid)?.uuid());
+        }
+
+        if let Some(revision) = &self.since {
+            for hrid in directory.changed_since(revision)? {
+                changed.push(find_requirement(tree, &hrid)?.uuid());
+            }
+        }
+
+        let report = ImpactReport::new(tree, &changed);
+        println!("{report}");
+
+        Ok(())
+    }
+}
+
+fn hrid_from_path(path: &Path) -> anyhow::Result<Hrid> {
+    let stem = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .with_context(|| format!("invalid requirement path: {}", path.display()))?;
+    Hrid::try_from(stem).with_context(|| format!("invalid requirement path: {}", path.display()))
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct Check {}
+
+impl Check {
+    #[instrument]
+    fn run(path: PathBuf) -> anyhow::Result<()> {
+        let directory = Directory::new(path).load_all()?;
+        let mut problems = 0;
+
+        for cycle in directory.find_cycles() {
+            println!("cycle: {cycle}");
+            problems += 1;
+        }
+
+        for (path, link) in directory.broken_links() {
+            println!("{}: broken link: {link}", path.display());
+            problems += 1;
+        }
+
+        for (child, parent, error) in directory.disallowed_parents() {
+            println!(
+                "{}: disallowed link to {parent}: {error}",
+                directory.path(&child).display()
+            );
+            problems += 1;
+        }
+
+        for (child, parent, error) in directory.unknown_relations() {
+            println!(
+                "{}: link to {parent}: {error}",
+                directory.path(&child).display()
+            );
+            problems += 1;
+        }
+
+        for (hrid, error) in directory.disallowed_kinds() {
+            println!("{}: {error}", directory.path(&hrid).display());
+            problems += 1;
+        }
+
+        for (hrid, error) in directory.missing_attributes() {
+            println!("{}: {error}", directory.path(&hrid).display());
+            problems += 1;
+        }
+
+        for rule in directory.config().coverage_rules() {
+            let report = CoverageReport::new(
+                directory.tree(),
+                &rule.kind,
+                &rule.covered_by,
+                &rule.namespace,
+                rule.relation.as_deref(),
+            );
+            let relation = rule
+                .relation
+                .as_ref()
+                .map(|relation| format!(" linked as {relation}"))
+                .unwrap_or_default();
+            for entry in report.uncovered() {
+                println!(
+                    "not covered: {} has no {} children{relation}",
+                    entry.hrid, rule.covered_by
+                );
+                problems += 1;
+            }
+        }
+
+        if problems == 0 {
+            println!("No problems found");
+            Ok(())
+        } else {
+            anyhow::bail!("found {problems} problem(s)")
+        }
+    }
+}
@@ -1,8 +1,14 @@
 pub mod requirement;
 pub use requirement::Requirement;
 
+mod attribute;
+pub use attribute::{AttributeError, AttributeSchema, AttributeType, AttributeValue, SchemaError};
+
 mod config;
-pub use config::Config;
+pub use config::{
+    Config, ConfigError, CoverageRule, DisallowedKindError, DisallowedParentError, Relation,
+    UnknownRelationError,
+};
 
 mod hrid;
 pub use hrid::{EmptyStringError, Hrid};
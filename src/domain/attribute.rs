@@ -0,0 +1,488 @@
+use std::{fmt, io};
+
+use borsh::BorshSerialize;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use super::config::suggest;
+
+/// The value of a custom attribute of a requirement.
+///
+/// Attributes are stored in the frontmatter of a requirement, under
+/// `attributes`. Dates are written as `YYYY-MM-DD` strings, so a value read
+/// from a file is only a [`AttributeValue::Date`] once it has been checked
+/// against a schema which declares it as a date.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttributeValue {
+    /// A string, or one of the values of an enumeration.
+    String(String),
+
+    /// A whole number.
+    Integer(i64),
+
+    /// Either `true` or `false`.
+    Boolean(bool),
+
+    /// A calendar date.
+    Date(NaiveDate),
+
+    /// A list of strings.
+    List(Vec<String>),
+}
+
+impl AttributeValue {
+    /// The value as a string, if it is a string or an enumeration value.
+    #[must_use]
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// The value as an integer, if it is one.
+    #[must_use]
+    pub const fn as_integer(&self) -> Option<i64> {
+        match self {
+            Self::Integer(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// The value as a boolean, if it is one.
+    #[must_use]
+    pub const fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Boolean(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// The value as a date, if it is one.
+    #[must_use]
+    pub const fn as_date(&self) -> Option<NaiveDate> {
+        match self {
+            Self::Date(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// The value as a list of strings, if it is one.
+    #[must_use]
+    pub fn as_list(&self) -> Option<&[String]> {
+        match self {
+            Self::List(values) => Some(values),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for AttributeValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::String(value) => write!(f, "{value}"),
+            Self::Integer(value) => write!(f, "{value}"),
+            Self::Boolean(value) => write!(f, "{value}"),
+            Self::Date(value) => write!(f, "{value}"),
+            Self::List(values) => write!(f, "{}", values.join(", ")),
+        }
+    }
+}
+
+impl Serialize for AttributeValue {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::String(value) => serializer.serialize_str(value),
+            Self::Integer(value) => serializer.serialize_i64(*value),
+            Self::Boolean(value) => serializer.serialize_bool(*value),
+            Self::Date(value) => serializer.collect_str(value),
+            Self::List(values) => Serialize::serialize(values, serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for AttributeValue {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl<'de> serde::de::Visitor<'de> for Visitor {
+            type Value = AttributeValue;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a string, integer, boolean or list of strings")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                Ok(AttributeValue::String(value.to_string()))
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, value: i64) -> Result<Self::Value, E> {
+                Ok(AttributeValue::Integer(value))
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, value: u64) -> Result<Self::Value, E> {
+                i64::try_from(value)
+                    .map(AttributeValue::Integer)
+                    .map_err(|_| E::invalid_value(serde::de::Unexpected::Unsigned(value), &self))
+            }
+
+            fn visit_bool<E: serde::de::Error>(self, value: bool) -> Result<Self::Value, E> {
+                Ok(AttributeValue::Boolean(value))
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> Result<Self::Value, A::Error> {
+                let mut values = Vec::new();
+                while let Some(value) = seq.next_element()? {
+                    values.push(value);
+                }
+                Ok(AttributeValue::List(values))
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
+impl BorshSerialize for AttributeValue {
+    fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        // Dates are encoded as the strings they are written as, so that the
+        // fingerprint doesn't depend on whether the value has been checked
+        // against a schema
+        match self {
+            Self::String(value) => BorshSerialize::serialize(&(0u8, value), writer),
+            Self::Date(value) => BorshSerialize::serialize(&(0u8, value.to_string()), writer),
+            Self::Integer(value) => BorshSerialize::serialize(&(1u8, value), writer),
+            Self::Boolean(value) => BorshSerialize::serialize(&(2u8, value), writer),
+            Self::List(values) => BorshSerialize::serialize(&(3u8, values), writer),
+        }
+    }
+}
+
+/// The declaration of a custom attribute in the configuration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttributeSchema {
+    /// The type of the attribute's values.
+    #[serde(flatten)]
+    pub kind: AttributeType,
+
+    /// Whether every requirement must set the attribute.
+    ///
+    /// A required attribute with a default is never missing.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub required: bool,
+
+    /// The value used when a requirement doesn't set the attribute.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<AttributeValue>,
+
+    /// Whether changing the attribute changes the requirement's fingerprint,
+    /// making links to it suspect.
+    ///
+    /// Defaults are not part of the fingerprint, only values which are set in
+    /// the requirement.
+    #[serde(default = "fingerprint_default", skip_serializing_if = "is_true")]
+    pub fingerprint: bool,
+}
+
+impl AttributeSchema {
+    /// Declare an optional attribute of the given type, with no default.
+    #[must_use]
+    pub const fn new(kind: AttributeType) -> Self {
+        Self {
+            kind,
+            required: false,
+            default: None,
+            fingerprint: true,
+        }
+    }
+
+    /// Check that a value has the declared type.
+    ///
+    /// Returns the value, converted to a date if the attribute is a date.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value does not have the declared type, or is not
+    /// one of the values of an enumeration.
+    pub fn check(
+        &self,
+        name: &str,
+        value: AttributeValue,
+    ) -> Result<AttributeValue, AttributeError> {
+        let mismatch = |value| AttributeError::Type {
+            name: name.to_string(),
+            expected: self.kind.to_string(),
+            value,
+        };
+
+        match (&self.kind, value) {
+            (AttributeType::Enum { values }, AttributeValue::String(value)) => {
+                if values.contains(&value) {
+                    Ok(AttributeValue::String(value))
+                } else {
+                    Err(AttributeError::NotAllowed {
+                        name: name.to_string(),
+                        suggestion: suggest(&value, values).map(ToString::to_string),
+                        value,
+                        allowed: values.clone(),
+                    })
+                }
+            }
+            (AttributeType::Date, AttributeValue::String(value)) => {
+                NaiveDate::parse_from_str(&value, "%Y-%m-%d")
+                    .map(AttributeValue::Date)
+                    .map_err(|_| mismatch(AttributeValue::String(value)))
+            }
+            (AttributeType::String, value @ AttributeValue::String(_))
+            | (AttributeType::Integer, value @ AttributeValue::Integer(_))
+            | (AttributeType::Boolean, value @ AttributeValue::Boolean(_))
+            | (AttributeType::Date, value @ AttributeValue::Date(_))
+            | (AttributeType::List, value @ AttributeValue::List(_)) => Ok(value),
+            (_, value) => Err(mismatch(value)),
+        }
+    }
+
+    /// Parse a value of the attribute from text, such as a command line
+    /// argument.
+    ///
+    /// Lists are separated by commas.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the text is not a valid value of the attribute.
+    pub fn parse(&self, name: &str, text: &str) -> Result<AttributeValue, AttributeError> {
+        let value = match self.kind {
+            AttributeType::Integer => text.parse().map(AttributeValue::Integer).ok(),
+            AttributeType::Boolean => text.parse().map(AttributeValue::Boolean).ok(),
+            AttributeType::List => Some(AttributeValue::List(
+                text.split(',')
+                    .map(|item| item.trim().to_string())
+                    .collect(),
+            )),
+            AttributeType::Enum { .. } | AttributeType::String | AttributeType::Date => None,
+        }
+        .unwrap_or_else(|| AttributeValue::String(text.to_string()));
+
+        self.check(name, value)
+    }
+}
+
+const fn fingerprint_default() -> bool {
+    true
+}
+
+#[expect(
+    clippy::trivially_copy_pass_by_ref,
+    reason = "serde passes fields by reference"
+)]
+const fn is_true(value: &bool) -> bool {
+    *value
+}
+
+/// The type of the values of a custom attribute.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum AttributeType {
+    /// One of a fixed set of strings.
+    Enum {
+        /// The allowed values.
+        values: Vec<String>,
+    },
+
+    /// A whole number.
+    Integer,
+
+    /// Any string.
+    String,
+
+    /// A calendar date, written as `YYYY-MM-DD`.
+    Date,
+
+    /// Either `true` or `false`.
+    Boolean,
+
+    /// A list of strings.
+    List,
+}
+
+impl fmt::Display for AttributeType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Enum { values } => write!(f, "one of {}", values.join(", ")),
+            Self::Integer => f.write_str("an integer"),
+            Self::String => f.write_str("a string"),
+            Self::Date => f.write_str("a date (YYYY-MM-DD)"),
+            Self::Boolean => f.write_str("true or false"),
+            Self::List => f.write_str("a list of strings"),
+        }
+    }
+}
+
+/// Error returned when the attributes of a requirement don't match the
+/// configured schema.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum AttributeError {
+    /// The attribute is not declared in the configuration.
+    #[error(
+        "attribute '{name}' is not declared{}",
+        suggestion.as_ref().map_or_else(String::new, |s| format!(" (did you mean '{s}'?)"))
+    )]
+    Undeclared {
+        /// The name of the attribute.
+        name: String,
+
+        /// The declared attribute which is closest to it, if any is close
+        /// enough.
+        suggestion: Option<String>,
+    },
+
+    /// A required attribute is not set.
+    #[error("missing required attribute '{0}'")]
+    Missing(String),
+
+    /// The value of the attribute has the wrong type.
+    #[error("attribute '{name}' must be {expected}, but is '{value}'")]
+    Type {
+        /// The name of the attribute.
+        name: String,
+
+        /// A description of the declared type.
+        expected: String,
+
+        /// The value of the attribute.
+        value: AttributeValue,
+    },
+
+    /// The value of an enumeration is not one of its allowed values.
+    #[error(
+        "attribute '{name}' must be one of {}, but is '{value}'{}",
+        allowed.join(", "),
+        suggestion.as_ref().map_or_else(String::new, |s| format!(" (did you mean '{s}'?)"))
+    )]
+    NotAllowed {
+        /// The name of the attribute.
+        name: String,
+
+        /// The value of the attribute.
+        value: String,
+
+        /// The allowed values.
+        allowed: Vec<String>,
+
+        /// The allowed value which is closest to it, if any is close enough.
+        suggestion: Option<String>,
+    },
+}
+
+/// Error returned when the attributes of a requirement don't match the
+/// configured schema, with every attribute which doesn't match.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub struct SchemaError(Vec<AttributeError>);
+
+impl SchemaError {
+    /// Collects attribute errors, returning `None` if there are none.
+    #[must_use]
+    pub fn from_vec(errors: Vec<AttributeError>) -> Option<Self> {
+        (!errors.is_empty()).then_some(Self(errors))
+    }
+
+    /// The errors, in attribute name order. There is at least one.
+    #[must_use]
+    pub fn errors(&self) -> &[AttributeError] {
+        &self.0
+    }
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let errors: Vec<String> = self.0.iter().map(ToString::to_string).collect();
+        f.write_str(&errors.join("; "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn priority() -> AttributeSchema {
+        AttributeSchema::new(AttributeType::Enum {
+            values: vec!["low".to_string(), "medium".to_string(), "high".to_string()],
+        })
+    }
+
+    #[test]
+    fn values_are_checked_against_their_type() {
+        let date = AttributeSchema::new(AttributeType::Date);
+        assert_eq!(
+            date.check("due", AttributeValue::String("2025-07-14".to_string())),
+            Ok(AttributeValue::Date(
+                NaiveDate::from_ymd_opt(2025, 7, 14).unwrap()
+            ))
+        );
+        assert_eq!(
+            date.check("due", AttributeValue::String("soon".to_string()))
+                .unwrap_err()
+                .to_string(),
+            "attribute 'due' must be a date (YYYY-MM-DD), but is 'soon'"
+        );
+
+        let integer = AttributeSchema::new(AttributeType::Integer);
+        assert!(integer.check("level", AttributeValue::Integer(3)).is_ok());
+        assert!(matches!(
+            integer.check("level", AttributeValue::Boolean(true)),
+            Err(AttributeError::Type { .. })
+        ));
+    }
+
+    #[test]
+    fn enum_values_suggest_the_closest_value() {
+        let error = priority()
+            .check("priority", AttributeValue::String("hgh".to_string()))
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "attribute 'priority' must be one of low, medium, high, but is 'hgh' (did you mean \
+             'high'?)"
+        );
+    }
+
+    #[test]
+    fn values_are_parsed_from_text() {
+        assert_eq!(
+            AttributeSchema::new(AttributeType::List).parse("owners", "alice, bob"),
+            Ok(AttributeValue::List(vec![
+                "alice".to_string(),
+                "bob".to_string()
+            ]))
+        );
+        assert_eq!(
+            AttributeSchema::new(AttributeType::Boolean).parse("safety", "true"),
+            Ok(AttributeValue::Boolean(true))
+        );
+        assert!(AttributeSchema::new(AttributeType::Integer)
+            .parse("level", "high")
+            .is_err());
+        assert!(priority().parse("priority", "low").is_ok());
+    }
+
+    #[test]
+    fn values_round_trip_through_yaml() {
+        for (yaml, value) in [
+            ("text\n", AttributeValue::String("text".to_string())),
+            ("3\n", AttributeValue::Integer(3)),
+            ("false\n", AttributeValue::Boolean(false)),
+            (
+                "- a\n- b\n",
+                AttributeValue::List(vec!["a".to_string(), "b".to_string()]),
+            ),
+        ] {
+            assert_eq!(serde_yaml::from_str::<AttributeValue>(yaml).unwrap(), value);
+            assert_eq!(serde_yaml::to_string(&value).unwrap(), yaml);
+        }
+
+        assert!(serde_yaml::from_str::<AttributeValue>("1.5").is_err());
+    }
+}
@@ -1,7 +1,10 @@
-use std::path::Path;
+use std::{collections::BTreeMap, io, path::Path};
 
 use serde::{Deserialize, Serialize};
 
+use super::{AttributeError, AttributeSchema};
+
+/// The configuration of a requirements root, read from its `config.toml`.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(from = "Versions", into = "Versions")]
 pub struct Config {
@@ -28,6 +31,25 @@ pub struct Config {
     /// Whether to allow markdown files with names that are valid HRIDs that are
     /// not correctly formatted
     pub allow_invalid: bool,
+
+    /// The kinds of requirements which each kind may have as parents.
+    ///
+    /// A kind which is not listed may have parents of any kind.
+    parent_kinds: BTreeMap<String, Vec<String>>,
+
+    /// The types of relationship which links between requirements may have,
+    /// by name.
+    ///
+    /// For example, 'verifies' or 'refines'.
+    relations: BTreeMap<String, Relation>,
+
+    /// The custom attributes which requirements may have, by name.
+    ///
+    /// For example, 'priority' or 'owner'.
+    attributes: BTreeMap<String, AttributeSchema>,
+
+    /// Rules requiring requirements of one kind to be covered by another.
+    coverage: Vec<CoverageRule>,
 }
 
 impl Default for Config {
@@ -37,21 +59,510 @@ impl Default for Config {
             digits: default_digits(),
             allow_unrecognised: false,
             allow_invalid: false,
+            parent_kinds: BTreeMap::new(),
+            relations: BTreeMap::new(),
+            attributes: BTreeMap::new(),
+            coverage: Vec::new(),
         }
     }
 }
 
+/// A type of relationship which a link between requirements may have.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Relation {
+    /// What the relationship means.
+    ///
+    /// For example, 'the child is a test which verifies the parent'.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+}
+
+/// A rule requiring every requirement of one kind to be covered by at least one
+/// requirement of another kind.
+///
+/// A requirement is covered by a kind if at least one of its children is of
+/// that kind.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoverageRule {
+    /// The kind of requirement which must be covered.
+    ///
+    /// For example, 'USR'.
+    pub kind: String,
+
+    /// The kind of requirement which must cover it.
+    ///
+    /// For example, 'SYS'.
+    pub covered_by: String,
+
+    /// The namespace the rule applies to.
+    ///
+    /// If this is empty, the rule applies to every namespace.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub namespace: Vec<String>,
+
+    /// The type of relationship which links must have to count towards
+    /// coverage.
+    ///
+    /// If this is not set, links of any type count.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relation: Option<String>,
+}
+
 impl Config {
     /// Loads the configuration from a TOML file at the given path.
-    pub fn load(path: &Path) -> Result<Self, String> {
-        let content = std::fs::read_to_string(path)
-            .map_err(|e| format!("Failed to read config file: {e}"))?;
-        toml::from_str(&content).map_err(|e| format!("Failed to parse config file: {e}"))
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not a valid
+    /// configuration, including if the default of a custom attribute doesn't
+    /// have the declared type.
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        #[derive(Deserialize)]
+        struct Version {
+            #[serde(rename = "_version")]
+            version: String,
+        }
+
+        let content = std::fs::read_to_string(path)?;
+
+        // Deserialising through the tagged `Versions` loses the location of
+        // errors within the file, so known versions are deserialised directly
+        let config: Self =
+            if toml::from_str::<Version>(&content).is_ok_and(|version| version.version == "1") {
+                let v1: V1 = toml::from_str(&content)?;
+                Versions::V1(v1).into()
+            } else {
+                toml::from_str(&content)?
+            };
+
+        for (name, attribute) in &config.attributes {
+            if let Some(default) = &attribute.default {
+                attribute
+                    .check(name, default.clone())
+                    .map_err(ConfigError::Default)?;
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// The coverage rules which `req check` enforces.
+    #[must_use]
+    pub fn coverage_rules(&self) -> &[CoverageRule] {
+        &self.coverage
+    }
+
+    /// The kinds of requirements that are allowed.
+    ///
+    /// If this is empty, all kinds are allowed.
+    #[must_use]
+    pub fn allowed_kinds(&self) -> &[String] {
+        &self.allowed_kinds
+    }
+
+    /// Check that a kind of requirement is allowed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `allowed_kinds` is not empty and does not contain
+    /// the kind. The error suggests the closest allowed kind, if there is one
+    /// which the kind could plausibly be a typo of.
+    pub fn check_kind(&self, kind: &str) -> Result<(), DisallowedKindError> {
+        if self.allowed_kinds.is_empty() || self.allowed_kinds.iter().any(|k| k == kind) {
+            return Ok(());
+        }
+
+        Err(DisallowedKindError {
+            kind: kind.to_string(),
+            suggestion: suggest(kind, &self.allowed_kinds).map(ToString::to_string),
+        })
+    }
+
+    /// The kinds of requirements which a kind may have as parents, or `None`
+    /// if it may have parents of any kind.
+    #[must_use]
+    pub fn allowed_parent_kinds(&self, kind: &str) -> Option<&[String]> {
+        self.parent_kinds.get(kind).map(Vec::as_slice)
+    }
+
+    /// Set the kinds of requirements which a kind may have as parents.
+    #[must_use]
+    pub fn with_parent_kinds(mut self, kind: String, parents: Vec<String>) -> Self {
+        self.parent_kinds.insert(kind, parents);
+        self
+    }
+
+    /// Check that a requirement of one kind may have a parent of another.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the parent kinds of the child's kind are declared,
+    /// and do not include the parent's kind.
+    pub fn check_parent_kind(
+        &self,
+        kind: &str,
+        parent_kind: &str,
+    ) -> Result<(), DisallowedParentError> {
+        match self.allowed_parent_kinds(kind) {
+            Some(allowed) if !allowed.iter().any(|k| k == parent_kind) => {
+                Err(DisallowedParentError {
+                    kind: kind.to_string(),
+                    parent_kind: parent_kind.to_string(),
+                    allowed: allowed.to_vec(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The types of relationship which links may have, by name.
+    #[must_use]
+    pub const fn relations(&self) -> &BTreeMap<String, Relation> {
+        &self.relations
+    }
+
+    /// Declare a type of relationship which links may have.
+    #[must_use]
+    pub fn with_relation(mut self, name: String, relation: Relation) -> Self {
+        self.relations.insert(name, relation);
+        self
+    }
+
+    /// Check that a type of relationship has been declared.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the relation is not declared in `relations`. The
+    /// error suggests the closest declared relation, if there is one which the
+    /// relation could plausibly be a typo of.
+    pub fn check_relation(&self, relation: &str) -> Result<(), UnknownRelationError> {
+        if self.relations.contains_key(relation) {
+            return Ok(());
+        }
+
+        let declared: Vec<String> = self.relations.keys().cloned().collect();
+        Err(UnknownRelationError {
+            relation: relation.to_string(),
+            suggestion: suggest(relation, &declared).map(ToString::to_string),
+        })
+    }
+
+    /// The custom attributes which requirements may have, by name.
+    #[must_use]
+    pub const fn attributes(&self) -> &BTreeMap<String, AttributeSchema> {
+        &self.attributes
+    }
+
+    /// Declare a custom attribute which requirements may have.
+    #[must_use]
+    pub fn with_attribute(mut self, name: String, schema: AttributeSchema) -> Self {
+        self.attributes.insert(name, schema);
+        self
+    }
+
+    /// Set the kinds of requirements that are allowed.
+    #[must_use]
+    pub fn with_allowed_kinds(mut self, kinds: Vec<String>) -> Self {
+        self.allowed_kinds = kinds;
+        self
+    }
+
+    /// The number of digits in the HRID.
+    #[must_use]
+    pub const fn digits(&self) -> usize {
+        self.digits
+    }
+
+    /// Set the number of digits in the HRID.
+    #[must_use]
+    pub const fn with_digits(mut self, digits: usize) -> Self {
+        self.digits = digits;
+        self
+    }
+
+    /// Render the configuration as TOML, with a comment explaining each
+    /// setting.
+    ///
+    /// Settings which are left at their defaults are written commented out, so
+    /// that the file documents every option that is available.
+    #[must_use]
+    pub fn to_commented_toml(&self) -> String {
+        let mut toml = String::from(
+            "# Configuration for the requirements in this directory.\n#\n# The version of the \
+             configuration format. Don't change this.\n_version = \"1\"\n\n",
+        );
+
+        toml.push_str(
+            "# The kinds of requirements that are allowed. This is the first component\n# of an \
+             HRID, such as 'USR' in 'USR-001'. If this is empty, or left out,\n# all kinds are \
+             allowed.\n",
+        );
+        if self.allowed_kinds.is_empty() {
+            toml.push_str("# allowed_kinds = [\"USR\", \"SYS\"]\n\n");
+        } else {
+            toml.push_str("allowed_kinds = ");
+            toml.push_str(&quote_list(&self.allowed_kinds));
+            toml.push_str("\n\n");
+        }
+
+        toml.push_str(
+            "# The number of digits in an HRID. Numbers are padded to this width with\n# leading \
+             zeros, so 3 digits gives 'USR-001'.\n",
+        );
+        toml.push_str(&setting("digits", &self.digits, &default_digits()));
+
+        toml.push_str(
+            "# Whether to allow markdown files whose names are not HRIDs, such as a\n# README. \
+             These files are ignored.\n",
+        );
+        toml.push_str(&setting(
+            "allow_unrecognised",
+            &self.allow_unrecognised,
+            &false,
+        ));
+
+        toml.push_str(
+            "# Whether to allow markdown files whose names are HRIDs, but which are\n# not valid \
+             requirements. These files are ignored.\n",
+        );
+        toml.push_str(&setting("allow_invalid", &self.allow_invalid, &false));
+
+        toml.push_str(
+            "# The kinds of requirements which each kind may have as parents. A kind\n# which \
+             isn't listed may have parents of any kind, and a kind listed with\n# no parent kinds \
+             may not have parents at all. Links which break these\n# rules are refused by 'req \
+             link' and reported by 'req check'.\n",
+        );
+        if self.parent_kinds.is_empty() {
+            toml.push_str("#\n# [parent_kinds]\n# SYS = [\"USR\"]\n# TST = [\"SWR\", \"SYS\"]\n\n");
+        } else {
+            toml.push_str("[parent_kinds]\n");
+            for (kind, parents) in &self.parent_kinds {
+                toml.push_str(&quote(kind));
+                toml.push_str(" = ");
+                toml.push_str(&quote_list(parents));
+                toml.push('\n');
+            }
+            toml.push('\n');
+        }
+
+        self.push_relations(&mut toml);
+        self.push_attributes(&mut toml);
+        self.push_coverage(&mut toml);
+
+        toml
+    }
+
+    /// Append the `relations` table to a commented TOML configuration.
+    fn push_relations(&self, toml: &mut String) {
+        toml.push_str(
+            "# The types of relationship which links between requirements may have.\n# Links are \
+             given a relation with 'req link --as <RELATION>', and reports\n# can be restricted \
+             to links of one relation.\n",
+        );
+        if self.relations.is_empty() {
+            toml.push_str(
+                "#\n# [relations.verifies]\n# description = \"The child is a test which verifies \
+                 the parent\"\n\n",
+            );
+        }
+        for (name, relation) in &self.relations {
+            toml.push_str("[relations.");
+            toml.push_str(&quote(name));
+            toml.push_str("]\n");
+            if !relation.description.is_empty() {
+                toml.push_str("description = ");
+                toml.push_str(&quote(&relation.description));
+                toml.push('\n');
+            }
+            toml.push('\n');
+        }
+    }
+
+    /// Append the `attributes` tables to a commented TOML configuration.
+    fn push_attributes(&self, toml: &mut String) {
+        #[derive(Serialize)]
+        struct Attributes<'a> {
+            attributes: &'a BTreeMap<String, AttributeSchema>,
+        }
+
+        toml.push_str(
+            "# Custom attributes which requirements may set in their frontmatter, under\n# \
+             'attributes'. The type of an attribute is one of 'string', 'integer',\n# 'boolean', \
+             'date' (written as \"YYYY-MM-DD\"), 'list' (of strings), or 'enum'\n# (one of \
+             'values'). An attribute may be required, and may have a default\n# which is used \
+             when a requirement doesn't set it. Changing an attribute\n# makes links to the \
+             requirement suspect, unless 'fingerprint' is false.\n",
+        );
+        if self.attributes.is_empty() {
+            toml.push_str(
+                "#\n# [attributes.priority]\n# type = \"enum\"\n# values = [\"low\", \"medium\", \
+                 \"high\"]\n# default = \"medium\"\n#\n# [attributes.owner]\n# type = \
+                 \"string\"\n# required = true\n# fingerprint = false\n\n",
+            );
+            return;
+        }
+
+        let attributes = Attributes {
+            attributes: &self.attributes,
+        };
+        toml.push_str(&toml::to_string(&attributes).expect("this must never fail"));
+        toml.push('\n');
+    }
+
+    /// Append the `coverage` rules to a commented TOML configuration.
+    fn push_coverage(&self, toml: &mut String) {
+        toml.push_str(
+            "# Rules requiring every requirement of one kind to be covered by at least\n# one \
+             requirement of another kind. These are enforced by 'req check'.\n",
+        );
+        if self.coverage.is_empty() {
+            toml.push_str(
+                "#\n# [[coverage]]\n# kind = \"USR\"\n# covered_by = \"SYS\"\n# namespace = \
+                 [\"COMPONENT\"] # optional\n# relation = \"verifies\" # optional\n",
+            );
+        }
+        for rule in &self.coverage {
+            toml.push_str("\n[[coverage]]\nkind = ");
+            toml.push_str(&quote(&rule.kind));
+            toml.push_str("\ncovered_by = ");
+            toml.push_str(&quote(&rule.covered_by));
+            toml.push('\n');
+            if !rule.namespace.is_empty() {
+                toml.push_str("namespace = ");
+                toml.push_str(&quote_list(&rule.namespace));
+                toml.push('\n');
+            }
+            if let Some(relation) = &rule.relation {
+                toml.push_str("relation = ");
+                toml.push_str(&quote(relation));
+                toml.push('\n');
+            }
+        }
+    }
+}
+
+/// Errors that can occur when loading a configuration file.
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    /// The file cannot be read.
+    #[error("failed to read config file: {0}")]
+    Io(#[from] io::Error),
+
+    /// The file is not a valid configuration. The error includes the location
+    /// of the problem.
+    #[error("failed to parse config file: {0}")]
+    Parse(#[from] toml::de::Error),
+
+    /// The default of a custom attribute doesn't have the declared type.
+    #[error("invalid default in config file: {0}")]
+    Default(AttributeError),
+}
+
+/// Error returned when a requirement may not have a parent of some kind.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "requirements of kind '{kind}' may not have parents of kind '{parent_kind}' ({})",
+    if allowed.is_empty() {
+        "they may not have parents".to_string()
+    } else {
+        format!("allowed parent kinds: {}", allowed.join(", "))
+    }
+)]
+pub struct DisallowedParentError {
+    /// The kind of the child requirement.
+    pub kind: String,
+
+    /// The kind of the parent requirement.
+    pub parent_kind: String,
+
+    /// The kinds of parent which are allowed.
+    pub allowed: Vec<String>,
+}
+
+/// Error returned when a type of relationship is not in `relations`.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "relation '{relation}' is not declared{}",
+    suggestion.as_ref().map_or_else(String::new, |s| format!(" (did you mean '{s}'?)"))
+)]
+pub struct UnknownRelationError {
+    /// The relation which is not declared.
+    pub relation: String,
+
+    /// The declared relation which is closest to it, if any is close enough.
+    pub suggestion: Option<String>,
+}
+
+/// Error returned when a kind of requirement is not in `allowed_kinds`.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "kind '{kind}' is not allowed{}",
+    suggestion.as_ref().map_or_else(String::new, |s| format!(" (did you mean '{s}'?)"))
+)]
+pub struct DisallowedKindError {
+    /// The kind which is not allowed.
+    pub kind: String,
+
+    /// The allowed kind which is closest to it, if any is close enough.
+    pub suggestion: Option<String>,
+}
+
+/// Finds the candidate closest to `kind`, ignoring case.
+///
+/// Candidates more than a third of the length of `kind` away (and at least one
+/// edit) are not considered close enough to suggest.
+pub(super) fn suggest<'a>(kind: &str, candidates: &'a [String]) -> Option<&'a str> {
+    let kind = kind.to_ascii_uppercase();
+    let threshold = (kind.chars().count() / 3).max(1);
+
+    candidates
+        .iter()
+        .map(|candidate| {
+            (
+                edit_distance(&kind, &candidate.to_ascii_uppercase()),
+                candidate,
+            )
+        })
+        .filter(|(distance, _)| *distance <= threshold)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.as_str())
+}
+
+/// The Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != *b);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
     }
+
+    previous[b.len()]
+}
+
+/// A line setting a value, which is commented out if it is the default.
+fn setting<T: PartialEq + std::fmt::Display>(key: &str, value: &T, default: &T) -> String {
+    let prefix = if value == default { "# " } else { "" };
+    format!("{prefix}{key} = {value}\n\n")
+}
+
+fn quote(value: &str) -> String {
+    toml::Value::String(value.to_string()).to_string()
+}
+
+fn quote_list(values: &[String]) -> String {
+    let quoted: Vec<String> = values.iter().map(|value| quote(value)).collect();
+    format!("[{}]", quoted.join(", "))
 }
 
 const fn default_digits() -> usize {
-    3
+    super::Hrid::DEFAULT_DIGITS
 }
 
 /// The serialized versions of the configuration.
@@ -61,40 +572,64 @@ const fn default_digits() -> usize {
 #[serde(tag = "_version")]
 enum Versions {
     #[serde(rename = "1")]
-    V1 {
-        #[serde(default, skip_serializing_if = "Vec::is_empty")]
-        allowed_kinds: Vec<String>,
+    V1(V1),
+}
 
-        /// The number of digits in the HRID.
-        ///
-        /// Digits are padded to this width with leading zeros.
-        ///
-        /// This is the second component of the HRID.
-        /// For example, '001' (3 digits) or '0001' (4 digits).
-        #[serde(default = "default_digits")]
-        digits: usize,
+/// The first version of the configuration.
+#[derive(Debug, Serialize, Deserialize)]
+struct V1 {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    allowed_kinds: Vec<String>,
 
-        #[serde(default)]
-        allow_unrecognised: bool,
+    /// The number of digits in the HRID.
+    ///
+    /// Digits are padded to this width with leading zeros.
+    ///
+    /// This is the second component of the HRID.
+    /// For example, '001' (3 digits) or '0001' (4 digits).
+    #[serde(default = "default_digits")]
+    digits: usize,
+
+    #[serde(default)]
+    allow_unrecognised: bool,
+
+    #[serde(default)]
+    allow_invalid: bool,
+
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    parent_kinds: BTreeMap<String, Vec<String>>,
+
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    relations: BTreeMap<String, Relation>,
 
-        #[serde(default)]
-        allow_invalid: bool,
-    },
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    attributes: BTreeMap<String, AttributeSchema>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    coverage: Vec<CoverageRule>,
 }
 
 impl From<Versions> for super::Config {
     fn from(versions: Versions) -> Self {
         match versions {
-            Versions::V1 {
+            Versions::V1(V1 {
                 allowed_kinds,
                 digits,
                 allow_unrecognised,
                 allow_invalid,
-            } => Self {
+                parent_kinds,
+                relations,
+                attributes,
+                coverage,
+            }) => Self {
                 allowed_kinds,
                 digits,
                 allow_unrecognised,
                 allow_invalid,
+                parent_kinds,
+                relations,
+                attributes,
+                coverage,
             },
         }
     }
@@ -102,18 +637,23 @@ impl From<Versions> for super::Config {
 
 impl From<super::Config> for Versions {
     fn from(config: super::Config) -> Self {
-        Self::V1 {
+        Self::V1(V1 {
             allowed_kinds: config.allowed_kinds,
             digits: config.digits,
             allow_unrecognised: config.allow_unrecognised,
             allow_invalid: config.allow_invalid,
-        }
+            parent_kinds: config.parent_kinds,
+            relations: config.relations,
+            attributes: config.attributes,
+            coverage: config.coverage,
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::{AttributeType, AttributeValue};
 
     #[test]
     fn empty_file_returns_default() {
@@ -122,4 +662,183 @@ mod tests {
         let actual: Config = toml::from_str(r#"_version = "1""#).unwrap();
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn commented_toml_round_trips() {
+        let configs = [
+            Config::default(),
+            Config::default()
+                .with_allowed_kinds(vec!["USR".to_string(), "SYS".to_string()])
+                .with_digits(4),
+            Config::default()
+                .with_parent_kinds("SYS".to_string(), vec!["USR".to_string()])
+                .with_parent_kinds("USR".to_string(), Vec::new()),
+            Config {
+                allow_unrecognised: true,
+                allow_invalid: true,
+                coverage: vec![CoverageRule {
+                    kind: "SYS".to_string(),
+                    covered_by: "TST".to_string(),
+                    namespace: vec!["COMPONENT".to_string()],
+                    relation: Some("verifies".to_string()),
+                }],
+                ..Config::default()
+            }
+            .with_relation(
+                "verifies".to_string(),
+                Relation {
+                    description: "The child verifies the parent".to_string(),
+                },
+            )
+            .with_relation("refines".to_string(), Relation::default()),
+            Config::default()
+                .with_attribute(
+                    "priority".to_string(),
+                    AttributeSchema {
+                        default: Some(AttributeValue::String("medium".to_string())),
+                        ..AttributeSchema::new(AttributeType::Enum {
+                            values: vec!["low".to_string(), "medium".to_string()],
+                        })
+                    },
+                )
+                .with_attribute(
+                    "owner".to_string(),
+                    AttributeSchema {
+                        required: true,
+                        fingerprint: false,
+                        ..AttributeSchema::new(AttributeType::String)
+                    },
+                )
+                .with_attribute(
+                    "reviewed".to_string(),
+                    AttributeSchema::new(AttributeType::Date),
+                ),
+        ];
+
+        for expected in configs {
+            let actual: Config = toml::from_str(&expected.to_commented_toml()).unwrap();
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn check_kind_suggests_closest_kind() {
+        let config = Config::default().with_allowed_kinds(vec![
+            "USR".to_string(),
+            "SYS".to_string(),
+            "TST".to_string(),
+        ]);
+
+        assert!(config.check_kind("SYS").is_ok());
+        assert!(Config::default().check_kind("ANYTHING").is_ok());
+
+        let error = config.check_kind("SYSS").unwrap_err();
+        assert_eq!(error.suggestion.as_deref(), Some("SYS"));
+        assert_eq!(
+            error.to_string(),
+            "kind 'SYSS' is not allowed (did you mean 'SYS'?)"
+        );
+
+        assert_eq!(
+            config.check_kind("tst").unwrap_err().suggestion.as_deref(),
+            Some("TST")
+        );
+        assert_eq!(config.check_kind("DESIGN").unwrap_err().suggestion, None);
+    }
+
+    #[test]
+    fn parent_kinds_are_checked() {
+        let config: Config = toml::from_str(
+            r#"
+            _version = "1"
+
+            [parent_kinds]
+            SYS = ["USR"]
+            TST = ["SWR", "SYS"]
+            USR = []
+            "#,
+        )
+        .unwrap();
+
+        assert!(config.check_parent_kind("SYS", "USR").is_ok());
+        assert!(config.check_parent_kind("TST", "SYS").is_ok());
+        assert!(config.check_parent_kind("DOC", "ANY").is_ok());
+        assert_eq!(config.allowed_parent_kinds("DOC"), None);
+
+        let error = config.check_parent_kind("TST", "USR").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "requirements of kind 'TST' may not have parents of kind 'USR' (allowed parent kinds: \
+             SWR, SYS)"
+        );
+
+        let error = config.check_parent_kind("USR", "USR").unwrap_err();
+        assert!(error.to_string().ends_with("(they may not have parents)"));
+    }
+
+    #[test]
+    fn relations_are_checked() {
+        let config: Config = toml::from_str(
+            r#"
+            _version = "1"
+
+            [relations.verifies]
+            description = "The child is a test which verifies the parent"
+
+            [relations.refines]
+            "#,
+        )
+        .unwrap();
+
+        assert!(config.check_relation("verifies").is_ok());
+        assert!(config.check_relation("refines").is_ok());
+        assert_eq!(
+            config.check_relation("verfies").unwrap_err().to_string(),
+            "relation 'verfies' is not declared (did you mean 'verifies'?)"
+        );
+        assert_eq!(
+            Config::default()
+                .check_relation("verifies")
+                .unwrap_err()
+                .suggestion,
+            None
+        );
+    }
+
+    #[test]
+    fn coverage_rules_are_parsed() {
+        let config: Config = toml::from_str(
+            r#"
+            _version = "1"
+
+            [[coverage]]
+            kind = "USR"
+            covered_by = "SYS"
+
+            [[coverage]]
+            kind = "SYS"
+            covered_by = "TST"
+            namespace = ["COMPONENT"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.coverage_rules(),
+            &[
+                CoverageRule {
+                    kind: "USR".to_string(),
+                    covered_by: "SYS".to_string(),
+                    namespace: Vec::new(),
+                    relation: None,
+                },
+                CoverageRule {
+                    kind: "SYS".to_string(),
+                    covered_by: "TST".to_string(),
+                    namespace: vec!["COMPONENT".to_string()],
+                    relation: None,
+                },
+            ]
+        );
+    }
 }
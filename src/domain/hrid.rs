@@ -1,4 +1,9 @@
-use std::{fmt, str::FromStr};
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
 
 use non_empty_string::NonEmptyString;
 
@@ -12,11 +17,19 @@ use non_empty_string::NonEmptyString;
 /// - `ID` is a positive integer (e.g. `001`, `123`)
 ///
 /// Examples: `URS-001`, `SYS-099`, `COMPONENT-SUBCOMPONENT-SYS-005`
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// HRIDs are ordered by namespace, then kind, then numeric ID, so that
+/// `SYS-999` sorts before `SYS-1000`.
+///
+/// The ID is padded with leading zeros to a number of digits, which is taken
+/// from the string an HRID is parsed from. The padding is only used for
+/// formatting, so `URS-1` and `URS-001` are equal.
+#[derive(Debug, Clone)]
 pub struct Hrid {
-    namespace: Vec<NonEmptyString>,
+    namespace: Box<[NonEmptyString]>,
     kind: NonEmptyString,
     id: usize,
+    digits: usize,
 }
 
 /// Error returned when the provided string is empty
@@ -25,6 +38,9 @@ pub struct Hrid {
 pub struct EmptyStringError;
 
 impl Hrid {
+    /// The number of digits an ID is padded to, unless otherwise specified.
+    pub const DEFAULT_DIGITS: usize = 3;
+
     /// Create an HRID with no namespace.
     ///
     /// # Errors
@@ -59,15 +75,16 @@ impl Hrid {
     }
 
     /// Internal constructor that doesn't validate (for use after validation).
-    const fn new_with_namespace_unchecked(
+    fn new_with_namespace_unchecked(
         namespace: Vec<NonEmptyString>,
         kind: NonEmptyString,
         id: usize,
     ) -> Self {
         Self {
-            namespace,
+            namespace: namespace.into_boxed_slice(),
             kind,
             id,
+            digits: Self::DEFAULT_DIGITS,
         }
     }
 
@@ -76,6 +93,18 @@ impl Hrid {
         self.namespace.iter().map(NonEmptyString::as_str).collect()
     }
 
+    /// Returns `true` if the namespace begins with the given segments.
+    ///
+    /// An empty prefix matches every HRID.
+    pub fn namespace_starts_with<S: AsRef<str>>(&self, prefix: &[S]) -> bool {
+        prefix.len() <= self.namespace.len()
+            && self
+                .namespace
+                .iter()
+                .zip(prefix)
+                .all(|(segment, expected)| segment.as_str() == expected.as_ref())
+    }
+
     /// Returns the kind component as a string.
     #[must_use]
     pub fn kind(&self) -> &str {
@@ -87,11 +116,62 @@ impl Hrid {
     pub const fn id(&self) -> usize {
         self.id
     }
+
+    /// Returns the number of digits the ID is padded to when formatted.
+    #[must_use]
+    pub const fn digits(&self) -> usize {
+        self.digits
+    }
+
+    /// Sets the number of digits the ID is padded to when formatted.
+    ///
+    /// IDs which are too large for this many digits are formatted in full.
+    #[must_use]
+    pub const fn with_digits(mut self, digits: usize) -> Self {
+        self.digits = digits;
+        self
+    }
+
+    fn key(&self) -> (&[NonEmptyString], &NonEmptyString, usize) {
+        (&self.namespace, &self.kind, self.id)
+    }
+}
+
+impl PartialEq for Hrid {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Hrid {}
+
+impl PartialOrd for Hrid {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Hrid {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl Hash for Hrid {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
+impl serde::Serialize for Hrid {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
 }
 
 impl fmt::Display for Hrid {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let id_str = format!("{:03}", self.id);
+        let id_str = format!("{:0width$}", self.id, width = self.digits);
         if self.namespace.is_empty() {
             write!(f, "{}-{}", self.kind, id_str)
         } else {
@@ -158,7 +238,7 @@ impl FromStr for Hrid {
             Vec::new()
         };
 
-        Ok(Self::new_with_namespace_unchecked(namespace, kind, id))
+        Ok(Self::new_with_namespace_unchecked(namespace, kind, id).with_digits(id_str.len()))
     }
 }
 
@@ -196,6 +276,16 @@ mod tests {
         assert_eq!(hrid.id(), 5);
     }
 
+    #[test]
+    fn namespace_prefix_matching() {
+        let hrid = Hrid::try_from("COMPONENT-SUBCOMPONENT-SYS-005").unwrap();
+        assert!(hrid.namespace_starts_with::<&str>(&[]));
+        assert!(hrid.namespace_starts_with(&["COMPONENT"]));
+        assert!(hrid.namespace_starts_with(&["COMPONENT", "SUBCOMPONENT"]));
+        assert!(!hrid.namespace_starts_with(&["SUBCOMPONENT"]));
+        assert!(!hrid.namespace_starts_with(&["COMPONENT", "SUBCOMPONENT", "SYS"]));
+    }
+
     #[test]
     fn hrid_creation_empty_kind_fails() {
         let result = Hrid::new(String::new(), 42);
@@ -257,6 +347,26 @@ mod tests {
         assert_eq!(format!("{hrid}"), "NS-HUGE-12345");
     }
 
+    #[test]
+    fn hrid_display_with_digits() {
+        let hrid = Hrid::new("SYS".to_string(), 1).unwrap().with_digits(4);
+        assert_eq!(format!("{hrid}"), "SYS-0001");
+
+        let hrid = Hrid::new("SYS".to_string(), 12345).unwrap().with_digits(4);
+        assert_eq!(format!("{hrid}"), "SYS-12345");
+    }
+
+    #[test]
+    fn digits_are_parsed_but_ignored_by_equality() {
+        let short = Hrid::try_from("URS-1").unwrap();
+        let long = Hrid::try_from("URS-0001").unwrap();
+        assert_eq!(short.digits(), 1);
+        assert_eq!(long.digits(), 4);
+        assert_eq!(long.to_string(), "URS-0001");
+        assert_eq!(short, long);
+        assert_eq!(short.cmp(&long), Ordering::Equal);
+    }
+
     #[test]
     fn try_from_valid_no_namespace() {
         let hrid = Hrid::try_from("URS-001").unwrap();
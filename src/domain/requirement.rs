@@ -1,6 +1,6 @@
 use std::{
-    collections::{BTreeSet, HashMap},
-    io,
+    collections::{BTreeMap, BTreeSet, HashMap},
+    io::{self, BufRead},
     path::Path,
 };
 
@@ -10,7 +10,10 @@ use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
 pub use crate::domain::requirement::storage::LoadError;
-use crate::domain::{requirement::storage::MarkdownRequirement, Hrid};
+use crate::domain::{
+    requirement::storage::MarkdownRequirement, AttributeError, AttributeSchema, AttributeValue,
+    Hrid, SchemaError,
+};
 
 mod storage;
 
@@ -29,15 +32,51 @@ pub struct Requirement {
 ///
 /// This contributes to the 'fingerprint' of the requirement
 #[derive(Debug, BorshSerialize, Clone, PartialEq)]
+#[expect(
+    clippy::struct_field_names,
+    reason = "the body of a requirement is its content"
+)]
 struct Content {
     content: String,
     tags: BTreeSet<String>,
+
+    /// Custom attributes, which are encoded separately since only some of them
+    /// contribute to the fingerprint.
+    #[borsh(skip)]
+    attributes: BTreeMap<String, Attribute>,
+}
+
+/// The value of a custom attribute, along with how it is treated.
+#[derive(Debug, Clone, PartialEq)]
+struct Attribute {
+    value: AttributeValue,
+
+    /// Whether the attribute contributes to the fingerprint.
+    fingerprint: bool,
+
+    /// Whether the value is a default from the schema, rather than being set
+    /// in the requirement. Defaults are not saved.
+    default: bool,
 }
 
 impl Content {
     fn fingerprint(&self) -> String {
         // encode using [borsh](https://borsh.io/)
-        let encoded = borsh::to_vec(self).expect("this should never fail");
+        let mut encoded = borsh::to_vec(self).expect("this should never fail");
+
+        // Attributes are only encoded if there are any, so that requirements
+        // without attributes keep the same fingerprint
+        let attributes: BTreeMap<&String, &AttributeValue> = self
+            .attributes
+            .iter()
+            .filter(|(_, attribute)| attribute.fingerprint && !attribute.default)
+            .map(|(name, attribute)| (name, &attribute.value))
+            .collect();
+        if !attributes.is_empty() {
+            attributes
+                .serialize(&mut encoded)
+                .expect("this should never fail");
+        }
 
         // generate a SHA256 hash
         let hash = Sha256::digest(encoded);
@@ -68,6 +107,10 @@ struct Metadata {
 pub struct Parent {
     pub hrid: Hrid,
     pub fingerprint: String,
+
+    /// The type of relationship between the child and the parent, such as
+    /// 'verifies'. Untyped links have no relation.
+    pub relation: Option<String>,
 }
 
 impl Requirement {
@@ -84,6 +127,7 @@ impl Requirement {
         let content = Content {
             content,
             tags: BTreeSet::default(),
+            attributes: BTreeMap::new(),
         };
 
         let metadata = Metadata {
@@ -104,6 +148,11 @@ impl Requirement {
         &self.content.content
     }
 
+    /// Set the body of the requirement.
+    pub fn set_content(&mut self, content: String) {
+        self.content.content = content;
+    }
+
     /// The tags on the requirement
     #[must_use]
     pub const fn tags(&self) -> &BTreeSet<String> {
@@ -125,6 +174,149 @@ impl Requirement {
         self.content.tags.insert(tag)
     }
 
+    /// Remove a tag from the requirement.
+    ///
+    /// returns 'true' if the tag was removed, or 'false' if it was not
+    /// present.
+    pub fn remove_tag(&mut self, tag: &str) -> bool {
+        self.content.tags.remove(tag)
+    }
+
+    /// The value of a custom attribute, including any default from the schema.
+    #[must_use]
+    pub fn attribute(&self, name: &str) -> Option<&AttributeValue> {
+        self.content
+            .attributes
+            .get(name)
+            .map(|attribute| &attribute.value)
+    }
+
+    /// Returns an iterator over the custom attributes, in name order.
+    pub fn attributes(&self) -> impl Iterator<Item = (&str, &AttributeValue)> {
+        self.content
+            .attributes
+            .iter()
+            .map(|(name, attribute)| (name.as_str(), &attribute.value))
+    }
+
+    /// Set the value of a custom attribute.
+    ///
+    /// The value is not checked against the schema until
+    /// [`Requirement::apply_schema`] is called. Returns the previous value, if
+    /// the attribute was set.
+    pub fn set_attribute(&mut self, name: String, value: AttributeValue) -> Option<AttributeValue> {
+        let fingerprint = self
+            .content
+            .attributes
+            .get(&name)
+            .is_none_or(|attribute| attribute.fingerprint);
+        self.content
+            .attributes
+            .insert(
+                name,
+                Attribute {
+                    value,
+                    fingerprint,
+                    default: false,
+                },
+            )
+            .filter(|attribute| !attribute.default)
+            .map(|attribute| attribute.value)
+    }
+
+    /// Remove a custom attribute from the requirement.
+    ///
+    /// Returns the removed value, if the attribute was set.
+    pub fn remove_attribute(&mut self, name: &str) -> Option<AttributeValue> {
+        self.content
+            .attributes
+            .remove(name)
+            .filter(|attribute| !attribute.default)
+            .map(|attribute| attribute.value)
+    }
+
+    /// Check the custom attributes against a schema, keyed by attribute name.
+    ///
+    /// Values are converted to the declared types, defaults are filled in for
+    /// attributes which aren't set, and attributes which don't contribute to
+    /// the fingerprint are excluded from it. Required attributes are not
+    /// checked; see [`Requirement::check_required`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for every attribute which is not declared or doesn't
+    /// have the declared type. Such attributes are left unchanged.
+    pub fn apply_schema(
+        &mut self,
+        schema: &BTreeMap<String, AttributeSchema>,
+    ) -> Result<(), SchemaError> {
+        let attributes = &mut self.content.attributes;
+        attributes.retain(|_, attribute| !attribute.default);
+
+        let mut errors = Vec::new();
+        for (name, attribute) in attributes.iter_mut() {
+            let Some(declaration) = schema.get(name) else {
+                let declared: Vec<String> = schema.keys().cloned().collect();
+                errors.push(AttributeError::Undeclared {
+                    name: name.clone(),
+                    suggestion: super::config::suggest(name, &declared).map(ToString::to_string),
+                });
+                continue;
+            };
+            match declaration.check(name, attribute.value.clone()) {
+                Ok(value) => attribute.value = value,
+                Err(e) => errors.push(e),
+            }
+            attribute.fingerprint = declaration.fingerprint;
+        }
+
+        for (name, declaration) in schema {
+            if attributes.contains_key(name) {
+                continue;
+            }
+            // Defaults are checked when the configuration is loaded
+            if let Some(default) = &declaration.default {
+                let value = declaration
+                    .check(name, default.clone())
+                    .unwrap_or_else(|_| default.clone());
+                attributes.insert(
+                    name.clone(),
+                    Attribute {
+                        value,
+                        fingerprint: declaration.fingerprint,
+                        default: true,
+                    },
+                );
+            }
+        }
+
+        SchemaError::from_vec(errors).map_or(Ok(()), Err)
+    }
+
+    /// Check that every required attribute in a schema is set.
+    ///
+    /// A required attribute with a default is never missing.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for every required attribute which isn't set.
+    pub fn check_required(
+        &self,
+        schema: &BTreeMap<String, AttributeSchema>,
+    ) -> Result<(), SchemaError> {
+        let missing = schema
+            .iter()
+            .filter(|(name, declaration)| {
+                declaration.required
+                    && declaration.default.is_none()
+                    && !self.content.attributes.contains_key(*name)
+            })
+            .map(|(name, _)| AttributeError::Missing(name.clone()))
+            .collect();
+
+        SchemaError::from_vec(missing).map_or(Ok(()), Err)
+    }
+
     /// The human-readable identifier for this requirement.
     ///
     /// In normal usage these should be stable
@@ -133,6 +325,14 @@ impl Requirement {
         &self.metadata.hrid
     }
 
+    /// Set the human-readable identifier.
+    ///
+    /// This is crate-private, since the HRID must stay consistent with the
+    /// filename and any indices keyed on it.
+    pub(crate) fn set_hrid(&mut self, hrid: Hrid) {
+        self.metadata.hrid = hrid;
+    }
+
     /// The unique, stable identifier of this requirement
     #[must_use]
     pub const fn uuid(&self) -> Uuid {
@@ -161,6 +361,19 @@ impl Requirement {
         self.metadata.parents.insert(parent_id, parent_info)
     }
 
+    /// Remove a parent from the requirement.
+    ///
+    /// Returns the removed parent, if the requirement had it.
+    pub fn remove_parent(&mut self, parent_id: Uuid) -> Option<Parent> {
+        self.metadata.parents.remove(&parent_id)
+    }
+
+    /// Return the link to a parent, if the requirement has it.
+    #[must_use]
+    pub fn parent(&self, parent_id: Uuid) -> Option<&Parent> {
+        self.metadata.parents.get(&parent_id)
+    }
+
     /// Return an iterator over the requirement's 'parents'
     pub fn parents(&self) -> impl Iterator<Item = (Uuid, &Parent)> {
         self.metadata
@@ -190,6 +403,19 @@ impl Requirement {
         Ok(MarkdownRequirement::load(path, hrid)?.try_into()?)
     }
 
+    /// Reads a requirement from markdown with YAML frontmatter.
+    ///
+    /// This is useful for parsing requirements which are not in the
+    /// filesystem, such as earlier revisions from version control.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the reader fails, or if the markdown has malformed
+    /// YAML frontmatter.
+    pub fn read<R: BufRead>(reader: &mut R, hrid: Hrid) -> Result<Self, LoadError> {
+        Ok(MarkdownRequirement::read(reader, hrid)?.try_into()?)
+    }
+
     /// Writes the requirement to the given file path.
     /// Creates the file if it doesn't exist, or overwrites it if it does.
     ///
@@ -206,15 +432,17 @@ impl Requirement {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::BTreeSet;
+    use std::collections::{BTreeMap, BTreeSet};
 
-    use super::Content;
+    use super::{Attribute, Content};
+    use crate::domain::AttributeValue;
 
     #[test]
     fn fingerprint_does_not_panic() {
         let content = Content {
             content: "Some string".to_string(),
             tags: ["tag1".to_string(), "tag2".to_string()].into(),
+            attributes: BTreeMap::new(),
         };
         content.fingerprint();
     }
@@ -224,10 +452,12 @@ mod tests {
         let content1 = Content {
             content: "Some string".to_string(),
             tags: ["tag1".to_string(), "tag2".to_string()].into(),
+            attributes: BTreeMap::new(),
         };
         let content2 = Content {
             content: "Some string".to_string(),
             tags: ["tag2".to_string(), "tag1".to_string()].into(),
+            attributes: BTreeMap::new(),
         };
         assert_eq!(content1.fingerprint(), content2.fingerprint());
     }
@@ -237,10 +467,12 @@ mod tests {
         let content1 = Content {
             content: "Some string".to_string(),
             tags: ["tag1".to_string()].into(),
+            attributes: BTreeMap::new(),
         };
         let content2 = Content {
             content: "Some string".to_string(),
             tags: ["tag1".to_string(), "tag2".to_string()].into(),
+            attributes: BTreeMap::new(),
         };
         assert_ne!(content1.fingerprint(), content2.fingerprint());
     }
@@ -250,11 +482,42 @@ mod tests {
         let content1 = Content {
             content: "Some string".to_string(),
             tags: BTreeSet::default(),
+            attributes: BTreeMap::new(),
         };
         let content2 = Content {
             content: "Other string".to_string(),
             tags: BTreeSet::default(),
+            attributes: BTreeMap::new(),
         };
         assert_ne!(content1.fingerprint(), content2.fingerprint());
     }
+
+    #[test]
+    fn only_set_attributes_affect_fingerprint() {
+        let mut content = Content {
+            content: "Some string".to_string(),
+            tags: BTreeSet::default(),
+            attributes: BTreeMap::new(),
+        };
+        let fingerprint = content.fingerprint();
+
+        let attribute = Attribute {
+            value: AttributeValue::String("high".to_string()),
+            fingerprint: true,
+            default: true,
+        };
+        content
+            .attributes
+            .insert("priority".to_string(), attribute.clone());
+        assert_eq!(content.fingerprint(), fingerprint);
+
+        content.attributes.insert(
+            "priority".to_string(),
+            Attribute {
+                default: false,
+                ..attribute
+            },
+        );
+        assert_ne!(content.fingerprint(), fingerprint);
+    }
 }
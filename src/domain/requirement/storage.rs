@@ -1,5 +1,5 @@
 use std::{
-    collections::BTreeSet,
+    collections::{BTreeMap, BTreeSet},
     fs::File,
     io::{self, BufRead, BufReader, BufWriter, Write},
     path::Path,
@@ -12,8 +12,8 @@ use uuid::Uuid;
 use super::Requirement;
 use crate::domain::{
     hrid,
-    requirement::{Content, Metadata},
-    Hrid,
+    requirement::{Attribute, Content, Metadata},
+    AttributeValue, Hrid,
 };
 
 #[derive(Debug, Clone)]
@@ -30,38 +30,33 @@ impl MarkdownRequirement {
         writer.write_all(result.as_bytes())
     }
 
-    fn read<R: BufRead>(reader: &mut R, hrid: Hrid) -> Result<Self, LoadError> {
+    /// Reads a requirement from markdown with YAML frontmatter.
+    pub fn read<R: BufRead>(reader: &mut R, hrid: Hrid) -> Result<Self, LoadError> {
         let mut lines = reader.lines();
 
         // Ensure frontmatter starts correctly
-        let first_line = lines
-            .next()
-            .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "Empty input"))?
-            .map_err(LoadError::from)?;
+        let first_line = lines.next().ok_or(LoadError::MissingFrontMatter)??;
 
         if first_line.trim() != "---" {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                "Expected frontmatter starting with '---'",
-            )
-            .into());
+            return Err(LoadError::MissingFrontMatter);
         }
 
-        // Collect lines until next '---'
-        let frontmatter = lines
-            .by_ref()
-            .map_while(|line| match line {
+        // Collect lines until next '---'. The frontmatter starts with an empty
+        // line in place of the opening '---', so that the line numbers in YAML
+        // errors are line numbers in the file.
+        let frontmatter = std::iter::once(Ok(String::new()))
+            .chain(lines.by_ref().map_while(|line| match line {
                 Ok(content) if content.trim() == "---" => None,
                 Ok(content) => Some(Ok(content)),
                 Err(e) => Some(Err(e)),
-            })
+            }))
             .collect::<Result<Vec<_>, _>>()?
             .join("\n");
 
         // The rest of the lines are Markdown content
         let content = lines.collect::<Result<Vec<_>, _>>()?.join("\n");
 
-        let front: FrontMatter = serde_yaml::from_str(&frontmatter)?;
+        let front = FrontMatter::from_yaml(&frontmatter)?;
 
         Ok(Self {
             frontmatter: front,
@@ -100,11 +95,22 @@ impl MarkdownRequirement {
 }
 
 #[derive(Debug, thiserror::Error)]
-#[error("failed to read from markdown")]
 pub enum LoadError {
+    #[error("file not found")]
     NotFound,
+
+    #[error("failed to read file: {0}")]
     Io(#[from] io::Error),
+
+    #[error("missing frontmatter: the file must start with a line containing '---'")]
+    MissingFrontMatter,
+
+    /// The frontmatter is not valid. The location in the error is relative to
+    /// the start of the file.
+    #[error("invalid frontmatter: {0}")]
     Yaml(#[from] serde_yaml::Error),
+
+    #[error("invalid HRID: {0}")]
     Hrid(#[from] hrid::Error),
 }
 
@@ -116,6 +122,7 @@ struct FrontMatter {
     created: DateTime<Utc>,
     tags: BTreeSet<String>,
     parents: Vec<Parent>,
+    attributes: BTreeMap<String, AttributeValue>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -127,6 +134,8 @@ pub struct Parent {
         deserialize_with = "hrid_from_string"
     )]
     hrid: Hrid,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    relation: Option<String>,
 }
 
 pub fn hrid_as_string<S>(hrid: &Hrid, serializer: S) -> Result<S::Ok, S::Error>
@@ -148,29 +157,59 @@ where
 #[serde(tag = "_version")]
 enum FrontMatterVersion {
     #[serde(rename = "1")]
-    V1 {
-        uuid: Uuid,
-        created: DateTime<Utc>,
-        #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
-        tags: BTreeSet<String>,
-        #[serde(default, skip_serializing_if = "Vec::is_empty")]
-        parents: Vec<Parent>,
-    },
+    V1(FrontMatterV1),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct FrontMatterV1 {
+    uuid: Uuid,
+    created: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    tags: BTreeSet<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    parents: Vec<Parent>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    attributes: BTreeMap<String, AttributeValue>,
+}
+
+impl FrontMatter {
+    /// Parses the frontmatter from YAML.
+    ///
+    /// Deserialising the tagged [`FrontMatterVersion`] buffers the input, which
+    /// loses the location of any errors in the fields. Instead, the version is
+    /// read first and the fields are then deserialised directly.
+    fn from_yaml(yaml: &str) -> Result<Self, serde_yaml::Error> {
+        #[derive(Deserialize)]
+        struct Version {
+            #[serde(rename = "_version")]
+            version: String,
+        }
+
+        match serde_yaml::from_str::<Version>(yaml) {
+            Ok(Version { version }) if version == "1" => {
+                serde_yaml::from_str::<FrontMatterV1>(yaml)
+                    .map(|v1| FrontMatterVersion::V1(v1).into())
+            }
+            _ => serde_yaml::from_str(yaml),
+        }
+    }
 }
 
 impl From<FrontMatterVersion> for FrontMatter {
     fn from(version: FrontMatterVersion) -> Self {
         match version {
-            FrontMatterVersion::V1 {
+            FrontMatterVersion::V1(FrontMatterV1 {
                 uuid,
                 created,
                 tags,
                 parents,
-            } => Self {
+                attributes,
+            }) => Self {
                 uuid,
                 created,
                 tags,
                 parents,
+                attributes,
             },
         }
     }
@@ -183,20 +222,27 @@ impl From<FrontMatter> for FrontMatterVersion {
             created,
             tags,
             parents,
+            attributes,
         } = front_matter;
-        Self::V1 {
+        Self::V1(FrontMatterV1 {
             uuid,
             created,
             tags,
             parents,
-        }
+            attributes,
+        })
     }
 }
 
 impl From<Requirement> for MarkdownRequirement {
     fn from(req: Requirement) -> Self {
         let Requirement {
-            content: Content { content, tags },
+            content:
+                Content {
+                    content,
+                    tags,
+                    attributes,
+                },
             metadata:
                 Metadata {
                     uuid,
@@ -212,11 +258,26 @@ impl From<Requirement> for MarkdownRequirement {
             tags,
             parents: parents
                 .into_iter()
-                .map(|(uuid, super::Parent { hrid, fingerprint })| Parent {
-                    uuid,
-                    fingerprint,
-                    hrid,
-                })
+                .map(
+                    |(
+                        uuid,
+                        super::Parent {
+                            hrid,
+                            fingerprint,
+                            relation,
+                        },
+                    )| Parent {
+                        uuid,
+                        fingerprint,
+                        hrid,
+                        relation,
+                    },
+                )
+                .collect(),
+            attributes: attributes
+                .into_iter()
+                .filter(|(_, attribute)| !attribute.default)
+                .map(|(name, attribute)| (name, attribute.value))
                 .collect(),
         };
 
@@ -240,6 +301,7 @@ impl TryFrom<MarkdownRequirement> for Requirement {
                     created,
                     tags,
                     parents,
+                    attributes,
                 },
             content,
         } = req;
@@ -251,19 +313,39 @@ impl TryFrom<MarkdownRequirement> for Requirement {
                     uuid,
                     fingerprint,
                     hrid: parent_hrid,
+                    relation,
                 } = parent;
                 Ok((
                     uuid,
                     super::Parent {
                         hrid: parent_hrid,
                         fingerprint,
+                        relation,
                     },
                 ))
             })
             .collect::<Result<_, Self::Error>>()?;
 
+        // Until a schema is applied, every attribute contributes to the
+        // fingerprint
+        let attributes = attributes
+            .into_iter()
+            .map(|(name, value)| {
+                let attribute = Attribute {
+                    value,
+                    fingerprint: true,
+                    default: false,
+                };
+                (name, attribute)
+            })
+            .collect();
+
         Ok(Self {
-            content: Content { content, tags },
+            content: Content {
+                content,
+                tags,
+                attributes,
+            },
             metadata: Metadata {
                 uuid,
                 hrid,
@@ -291,12 +373,14 @@ mod tests {
             uuid: Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap(),
             fingerprint: "fingerprint1".to_string(),
             hrid: "REQ-PARENT-001".parse().unwrap(),
+            relation: None,
         }];
         FrontMatter {
             uuid,
             created,
             tags,
             parents,
+            attributes: BTreeMap::new(),
         }
     }
 
@@ -331,6 +415,64 @@ This is a paragraph.
         assert_eq!(expected, &actual);
     }
 
+    #[test]
+    fn parent_relation_round_trip() {
+        let hrid = "TST-001".parse().unwrap();
+        let expected = r"---
+_version: '1'
+uuid: 12b3f5c5-b1a8-4aa8-a882-20ff1c2aab53
+created: 2025-07-14T07:15:00Z
+parents:
+- uuid: 550e8400-e29b-41d4-a716-446655440000
+  fingerprint: fingerprint1
+  hrid: SYS-001
+  relation: verifies
+---
+Test content
+";
+
+        let mut reader = Cursor::new(expected);
+        let requirement = MarkdownRequirement::read(&mut reader, hrid).unwrap();
+        assert_eq!(
+            requirement.frontmatter.parents[0].relation.as_deref(),
+            Some("verifies")
+        );
+
+        let mut bytes: Vec<u8> = vec![];
+        requirement.write(&mut bytes).unwrap();
+        assert_eq!(expected, String::from_utf8(bytes).unwrap());
+    }
+
+    #[test]
+    fn attributes_round_trip() {
+        let hrid = "USR-001".parse().unwrap();
+        let expected = r"---
+_version: '1'
+uuid: 12b3f5c5-b1a8-4aa8-a882-20ff1c2aab53
+created: 2025-07-14T07:15:00Z
+attributes:
+  level: 3
+  owners:
+  - alice
+  - bob
+  priority: high
+  safety: true
+---
+Content
+";
+
+        let mut reader = Cursor::new(expected);
+        let requirement = MarkdownRequirement::read(&mut reader, hrid).unwrap();
+        assert_eq!(
+            requirement.frontmatter.attributes["level"],
+            AttributeValue::Integer(3)
+        );
+
+        let mut bytes: Vec<u8> = vec![];
+        requirement.write(&mut bytes).unwrap();
+        assert_eq!(expected, String::from_utf8(bytes).unwrap());
+    }
+
     #[test]
     fn markdown_minimal_content() {
         let hrid = Hrid::new("REQ".to_string(), 1).unwrap();
@@ -395,7 +537,7 @@ Line 4
         let mut reader = Cursor::new(content);
         let result = MarkdownRequirement::read(&mut reader, hrid);
 
-        assert!(result.is_err());
+        assert!(matches!(result, Err(LoadError::MissingFrontMatter)));
     }
 
     #[test]
@@ -427,6 +569,25 @@ Content";
         assert!(matches!(result, Err(LoadError::Yaml(_))));
     }
 
+    #[test]
+    fn yaml_error_location_is_relative_to_file() {
+        let hrid = Hrid::new("REQ".to_string(), 1).unwrap();
+        let content = r"---
+_version: '1'
+uuid: 12b3f5c5-b1a8-4aa8-a882-20ff1c2aab53
+created: not-a-date
+---
+Content";
+
+        let mut reader = Cursor::new(content);
+        let Err(LoadError::Yaml(error)) = MarkdownRequirement::read(&mut reader, hrid) else {
+            panic!("expected a YAML error");
+        };
+
+        assert_eq!(error.location().unwrap().line(), 4);
+        assert!(error.to_string().contains("line 4"));
+    }
+
     #[test]
     fn empty_input() {
         let hrid = Hrid::new("REQ".to_string(), 1).unwrap();
@@ -435,7 +596,7 @@ Content";
         let mut reader = Cursor::new(content);
         let result = MarkdownRequirement::read(&mut reader, hrid);
 
-        assert!(result.is_err());
+        assert!(matches!(result, Err(LoadError::MissingFrontMatter)));
     }
 
     #[test]
@@ -497,6 +658,7 @@ Content";
             uuid: Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap(),
             fingerprint: "fp1".to_string(),
             hrid: Hrid::new("REQ".to_string(), 1).unwrap(),
+            relation: None,
         }];
 
         let frontmatter = FrontMatter {
@@ -504,6 +666,10 @@ Content";
             created,
             tags,
             parents,
+            attributes: BTreeMap::from([(
+                "priority".to_string(),
+                AttributeValue::String("high".to_string()),
+            )]),
         };
         let version: FrontMatterVersion = frontmatter.clone().into();
         let back_to_frontmatter: FrontMatter = version.into();
@@ -521,6 +687,7 @@ Content";
             uuid,
             fingerprint: fingerprint.clone(),
             hrid: hrid.clone(),
+            relation: None,
         };
 
         assert_eq!(parent.uuid, uuid);
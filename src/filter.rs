@@ -0,0 +1,147 @@
+//! Selecting requirements by their properties and links
+
+use chrono::{DateTime, Utc};
+
+use crate::{Requirement, Tree};
+
+/// Criteria for selecting requirements from a [`Tree`].
+///
+/// A requirement is selected if it meets every criterion which is set. The
+/// default filter selects every requirement.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Filter {
+    /// Only select requirements of one of these kinds.
+    ///
+    /// If empty, requirements of any kind are selected.
+    pub kinds: Vec<String>,
+
+    /// Only select requirements whose namespace starts with these segments.
+    pub namespace: Vec<String>,
+
+    /// Only select requirements which have all of these tags.
+    pub tags: Vec<String>,
+
+    /// Only select requirements which have no parents.
+    pub no_parents: bool,
+
+    /// Only select requirements which have no children.
+    pub no_children: bool,
+
+    /// Only select requirements with at least one suspect link to a parent.
+    pub suspect: bool,
+
+    /// Only select requirements created at or after this time.
+    pub created_after: Option<DateTime<Utc>>,
+
+    /// Only select requirements created before this time.
+    pub created_before: Option<DateTime<Utc>>,
+}
+
+impl Filter {
+    /// Whether the given requirement meets every criterion.
+    #[must_use]
+    pub fn matches(&self, tree: &Tree, requirement: &Requirement) -> bool {
+        let hrid = requirement.hrid();
+
+        (self.kinds.is_empty() || self.kinds.iter().any(|kind| kind == hrid.kind()))
+            && hrid.namespace_starts_with(&self.namespace)
+            && self.tags.iter().all(|tag| requirement.tags().contains(tag))
+            && (!self.no_parents || requirement.parents().next().is_none())
+            && (!self.no_children || tree.children(requirement.uuid()).next().is_none())
+            && (!self.suspect || has_suspect_parent(tree, requirement))
+            && self
+                .created_after
+                .is_none_or(|after| requirement.created() >= after)
+            && self
+                .created_before
+                .is_none_or(|before| requirement.created() < before)
+    }
+
+    /// Selects the matching requirements from the tree, in HRID order.
+    #[must_use]
+    pub fn apply<'a>(&self, tree: &'a Tree) -> Vec<&'a Requirement> {
+        let mut requirements: Vec<&Requirement> = tree
+            .requirements()
+            .filter(|requirement| self.matches(tree, requirement))
+            .collect();
+        requirements.sort_by(|a, b| a.hrid().cmp(b.hrid()));
+        requirements
+    }
+}
+
+fn has_suspect_parent(tree: &Tree, requirement: &Requirement) -> bool {
+    requirement
+        .parents()
+        .any(|(parent_id, _)| tree.is_suspect(requirement.uuid(), parent_id) == Some(true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Filter;
+    use crate::{Hrid, Requirement, Tree};
+
+    fn tree() -> Tree {
+        let mut tree = Tree::default();
+
+        let usr = Requirement::new(Hrid::try_from("USR-001").unwrap(), String::new());
+        let mut sys = Requirement::new(Hrid::try_from("SYS-1000").unwrap(), String::new());
+        sys.add_tag("safety".to_string());
+        let other = Requirement::new(Hrid::try_from("SYS-999").unwrap(), String::new());
+        let namespaced = Requirement::new(Hrid::try_from("AUTH-SYS-001").unwrap(), String::new());
+        let (usr_id, sys_id) = (usr.uuid(), sys.uuid());
+
+        for requirement in [usr, sys, other, namespaced] {
+            tree.insert(requirement);
+        }
+        tree.link(sys_id, usr_id).unwrap();
+
+        tree
+    }
+
+    fn hrids(tree: &Tree, filter: &Filter) -> Vec<String> {
+        filter
+            .apply(tree)
+            .iter()
+            .map(|requirement| requirement.hrid().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn default_filter_selects_everything_in_natural_order() {
+        assert_eq!(
+            hrids(&tree(), &Filter::default()),
+            vec!["SYS-999", "SYS-1000", "USR-001", "AUTH-SYS-001"]
+        );
+    }
+
+    #[test]
+    fn criteria_are_combined() {
+        let tree = tree();
+
+        let filter = Filter {
+            kinds: vec!["SYS".to_string()],
+            no_parents: true,
+            ..Filter::default()
+        };
+        assert_eq!(hrids(&tree, &filter), vec!["SYS-999", "AUTH-SYS-001"]);
+
+        let filter = Filter {
+            namespace: vec!["AUTH".to_string()],
+            ..Filter::default()
+        };
+        assert_eq!(hrids(&tree, &filter), vec!["AUTH-SYS-001"]);
+
+        let filter = Filter {
+            tags: vec!["safety".to_string()],
+            ..Filter::default()
+        };
+        assert_eq!(hrids(&tree, &filter), vec!["SYS-1000"]);
+
+        let filter = Filter {
+            kinds: vec!["USR".to_string()],
+            no_children: true,
+            ..Filter::default()
+        };
+        assert!(hrids(&tree, &filter).is_empty());
+    }
+}
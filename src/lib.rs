@@ -3,7 +3,27 @@
 //! Requirements are markdown documents stored in a directory.
 
 mod domain;
-pub use domain::{EmptyStringError, Hrid, Requirement};
+pub use domain::{
+    AttributeSchema, AttributeType, AttributeValue, Config, CoverageRule, EmptyStringError, Hrid,
+    Relation, Requirement,
+};
+
+mod filter;
+pub use filter::Filter;
+
+mod search;
+pub use search::{MatchedLine, Search, SearchResult};
 
 mod storage;
-pub use storage::Directory;
+pub use storage::{
+    BrokenLink, BrokenLinkKind, Cycle, DeletePolicy, Directory, Integration, SuspectLink, Tree,
+};
+
+mod query;
+pub use query::{ParseError, Query};
+
+mod report;
+pub use report::{
+    Clusters, CoverageEntry, CoverageReport, Details, Graph, GraphEdge, ImpactReport, Impacted,
+    LinkDetails, LinkStatus, Matrix, MatrixCell, MatrixRow,
+};
@@ -0,0 +1,509 @@
+//! A small query language for selecting requirements
+//!
+//! Queries combine predicates with `and`, `or`, `not` and parentheses. For
+//! example:
+//!
+//! ```text
+//! kind = SYS and tag:safety and not covered_by(TST) and descendant_of(USR-002)
+//! ```
+//!
+//! The available predicates are:
+//!
+//! - `kind = KIND` and `kind != KIND`
+//! - `namespace = NS`, which matches namespaces starting with `NS`
+//! - `hrid = HRID`
+//! - `tag:TAG`
+//! - `covered_by(KIND)`, which matches requirements with a child of that kind
+//! - `child_of(HRID)` and `parent_of(HRID)`, for direct links
+//! - `descendant_of(HRID)` and `ancestor_of(HRID)`, for indirect links
+//! - `suspect`, which matches requirements with a suspect link to a parent
+//! - `root` and `leaf`, which match requirements with no parents or no children
+//!
+//! Values may be quoted with double quotes if they contain spaces or other
+//! special characters.
+
+use std::{collections::HashSet, fmt, str::FromStr};
+
+use uuid::Uuid;
+
+use crate::{Hrid, Requirement, Tree};
+
+/// A parsed query expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Query {
+    /// Both sub-queries match.
+    And(Box<Self>, Box<Self>),
+
+    /// Either sub-query matches.
+    Or(Box<Self>, Box<Self>),
+
+    /// The sub-query does not match.
+    Not(Box<Self>),
+
+    /// The requirement is of the given kind.
+    Kind(String),
+
+    /// The requirement's namespace starts with the given segments.
+    Namespace(Vec<String>),
+
+    /// The requirement has the given HRID.
+    Hrid(Hrid),
+
+    /// The requirement has the given tag.
+    Tag(String),
+
+    /// The requirement has a child of the given kind.
+    CoveredBy(String),
+
+    /// The requirement is a direct child of the given requirement.
+    ChildOf(Hrid),
+
+    /// The requirement is a direct parent of the given requirement.
+    ParentOf(Hrid),
+
+    /// The requirement is a descendant of the given requirement.
+    DescendantOf(Hrid),
+
+    /// The requirement is an ancestor of the given requirement.
+    AncestorOf(Hrid),
+
+    /// The requirement has a suspect link to a parent.
+    Suspect,
+
+    /// The requirement has no parents.
+    Root,
+
+    /// The requirement has no children.
+    Leaf,
+}
+
+/// An error encountered while parsing a query.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{message} at column {column}")]
+pub struct ParseError {
+    /// The column (counted in characters, starting from 1) of the error.
+    pub column: usize,
+
+    /// A description of the error.
+    pub message: String,
+}
+
+impl Query {
+    /// Parses a query expression.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error pointing at the column where the query is invalid.
+    pub fn parse(query: &str) -> Result<Self, ParseError> {
+        let tokens = tokenize(query)?;
+        let mut parser = Parser {
+            tokens,
+            position: 0,
+        };
+
+        let expression = parser.or()?;
+        match parser.peek() {
+            (Token::End, _) => Ok(expression),
+            (token, column) => Err(ParseError {
+                column,
+                message: format!("unexpected {token}"),
+            }),
+        }
+    }
+
+    /// Finds the requirements in the tree which match the query.
+    ///
+    /// Returns their UUIDs, in HRID order.
+    #[must_use]
+    pub fn evaluate(&self, tree: &Tree) -> Vec<Uuid> {
+        let mut matches: Vec<&Requirement> = self
+            .select(tree)
+            .into_iter()
+            .filter_map(|uuid| tree.requirement(uuid))
+            .collect();
+        matches.sort_by(|a, b| a.hrid().cmp(b.hrid()));
+        matches.into_iter().map(Requirement::uuid).collect()
+    }
+
+    fn select(&self, tree: &Tree) -> HashSet<Uuid> {
+        let filter = |predicate: &dyn Fn(&Requirement) -> bool| {
+            tree.requirements()
+                .filter(|requirement| predicate(requirement))
+                .map(Requirement::uuid)
+                .collect()
+        };
+        let related = |hrid: &Hrid, relatives: &dyn Fn(Uuid) -> HashSet<Uuid>| {
+            tree.find_by_hrid(hrid)
+                .map(|requirement| relatives(requirement.uuid()))
+                .unwrap_or_default()
+        };
+
+        match self {
+            Self::And(left, right) => {
+                let left = left.select(tree);
+                right
+                    .select(tree)
+                    .into_iter()
+                    .filter(|uuid| left.contains(uuid))
+                    .collect()
+            }
+            Self::Or(left, right) => {
+                let mut left = left.select(tree);
+                left.extend(right.select(tree));
+                left
+            }
+            Self::Not(query) => {
+                let excluded = query.select(tree);
+                filter(&|requirement| !excluded.contains(&requirement.uuid()))
+            }
+            Self::Kind(kind) => filter(&|requirement| requirement.hrid().kind() == kind),
+            Self::Namespace(namespace) => {
+                filter(&|requirement| requirement.hrid().namespace_starts_with(namespace))
+            }
+            Self::Hrid(hrid) => filter(&|requirement| requirement.hrid() == hrid),
+            Self::Tag(tag) => filter(&|requirement| requirement.tags().contains(tag)),
+            Self::CoveredBy(kind) => filter(&|requirement| {
+                tree.children(requirement.uuid()).any(|child| {
+                    tree.requirement(child)
+                        .is_some_and(|child| child.hrid().kind() == kind)
+                })
+            }),
+            Self::ChildOf(hrid) => related(hrid, &|uuid| tree.children(uuid).collect()),
+            Self::ParentOf(hrid) => related(hrid, &|uuid| tree.parents(uuid).collect()),
+            Self::DescendantOf(hrid) => related(hrid, &|uuid| tree.descendants(uuid).collect()),
+            Self::AncestorOf(hrid) => related(hrid, &|uuid| tree.ancestors(uuid).collect()),
+            Self::Suspect => {
+                let suspect: HashSet<Uuid> =
+                    tree.suspect_links().iter().map(|link| link.child).collect();
+                filter(&|requirement| suspect.contains(&requirement.uuid()))
+            }
+            Self::Root => filter(&|requirement| requirement.parents().next().is_none()),
+            Self::Leaf => filter(&|requirement| tree.children(requirement.uuid()).next().is_none()),
+        }
+    }
+}
+
+impl FromStr for Query {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    LeftParen,
+    RightParen,
+    Equals,
+    NotEquals,
+    Colon,
+    Word(String),
+    Quoted(String),
+    End,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LeftParen => write!(f, "'('"),
+            Self::RightParen => write!(f, "')'"),
+            Self::Equals => write!(f, "'='"),
+            Self::NotEquals => write!(f, "'!='"),
+            Self::Colon => write!(f, "':'"),
+            Self::Word(word) => write!(f, "'{word}'"),
+            Self::Quoted(text) => write!(f, "\"{text}\""),
+            Self::End => write!(f, "end of query"),
+        }
+    }
+}
+
+const fn is_word_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')
+}
+
+/// Splits a query into tokens, each paired with its column.
+fn tokenize(query: &str) -> Result<Vec<(Token, usize)>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = query.chars().enumerate().peekable();
+
+    while let Some((index, c)) = chars.next() {
+        let column = index + 1;
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '(' => Token::LeftParen,
+            ')' => Token::RightParen,
+            '=' => Token::Equals,
+            ':' => Token::Colon,
+            '!' if chars.next_if(|&(_, c)| c == '=').is_some() => Token::NotEquals,
+            '"' => {
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '"')) => break,
+                        Some((_, c)) => text.push(c),
+                        None => {
+                            return Err(ParseError {
+                                column,
+                                message: "unterminated string".to_string(),
+                            });
+                        }
+                    }
+                }
+                Token::Quoted(text)
+            }
+            c if is_word_char(c) => {
+                let mut word = c.to_string();
+                while let Some((_, c)) = chars.next_if(|&(_, c)| is_word_char(c)) {
+                    word.push(c);
+                }
+                Token::Word(word)
+            }
+            c => {
+                return Err(ParseError {
+                    column,
+                    message: format!("unexpected character '{c}'"),
+                });
+            }
+        };
+        tokens.push((token, column));
+    }
+
+    tokens.push((Token::End, query.chars().count() + 1));
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> (Token, usize) {
+        self.tokens[self.position.min(self.tokens.len() - 1)].clone()
+    }
+
+    fn next(&mut self) -> (Token, usize) {
+        let token = self.peek();
+        if token.0 != Token::End {
+            self.position += 1;
+        }
+        token
+    }
+
+    fn next_if_keyword(&mut self, keyword: &str) -> bool {
+        let is_keyword = matches!(&self.peek().0, Token::Word(word) if word == keyword);
+        if is_keyword {
+            self.position += 1;
+        }
+        is_keyword
+    }
+
+    fn expect(&mut self, expected: &Token) -> Result<(), ParseError> {
+        match self.next() {
+            (token, _) if token == *expected => Ok(()),
+            (token, column) => Err(ParseError {
+                column,
+                message: format!("expected {expected}, found {token}"),
+            }),
+        }
+    }
+
+    fn or(&mut self) -> Result<Query, ParseError> {
+        let mut query = self.and()?;
+        while self.next_if_keyword("or") {
+            query = Query::Or(Box::new(query), Box::new(self.and()?));
+        }
+        Ok(query)
+    }
+
+    fn and(&mut self) -> Result<Query, ParseError> {
+        let mut query = self.unary()?;
+        while self.next_if_keyword("and") {
+            query = Query::And(Box::new(query), Box::new(self.unary()?));
+        }
+        Ok(query)
+    }
+
+    fn unary(&mut self) -> Result<Query, ParseError> {
+        if self.next_if_keyword("not") {
+            return Ok(Query::Not(Box::new(self.unary()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Query, ParseError> {
+        let (token, column) = self.next();
+        let name = match token {
+            Token::LeftParen => {
+                let query = self.or()?;
+                self.expect(&Token::RightParen)?;
+                return Ok(query);
+            }
+            Token::Word(name) if !matches!(name.as_str(), "and" | "or" | "not") => name,
+            token => {
+                return Err(ParseError {
+                    column,
+                    message: format!("expected a predicate, found {token}"),
+                });
+            }
+        };
+
+        let error = |message: String| ParseError { column, message };
+
+        match self.peek().0 {
+            Token::Equals | Token::NotEquals => {
+                let (operator, _) = self.next();
+                let query = self.comparison(&name, column)?;
+                Ok(if operator == Token::NotEquals {
+                    Query::Not(Box::new(query))
+                } else {
+                    query
+                })
+            }
+            Token::Colon => {
+                self.next();
+                if name != "tag" {
+                    return Err(error(format!("unknown field '{name}'")));
+                }
+                Ok(Query::Tag(self.value()?.0))
+            }
+            Token::LeftParen => {
+                self.next();
+                let query = match name.as_str() {
+                    "covered_by" => Query::CoveredBy(self.value()?.0),
+                    "child_of" => Query::ChildOf(self.hrid()?),
+                    "parent_of" => Query::ParentOf(self.hrid()?),
+                    "descendant_of" => Query::DescendantOf(self.hrid()?),
+                    "ancestor_of" => Query::AncestorOf(self.hrid()?),
+                    _ => return Err(error(format!("unknown function '{name}'"))),
+                };
+                self.expect(&Token::RightParen)?;
+                Ok(query)
+            }
+            _ => match name.as_str() {
+                "suspect" => Ok(Query::Suspect),
+                "root" => Ok(Query::Root),
+                "leaf" => Ok(Query::Leaf),
+                _ => Err(error(format!("unknown predicate '{name}'"))),
+            },
+        }
+    }
+
+    fn comparison(&mut self, field: &str, column: usize) -> Result<Query, ParseError> {
+        match field {
+            "kind" => Ok(Query::Kind(self.value()?.0)),
+            "namespace" => Ok(Query::Namespace(
+                self.value()?.0.split('-').map(str::to_string).collect(),
+            )),
+            "hrid" => Ok(Query::Hrid(self.hrid()?)),
+            _ => Err(ParseError {
+                column,
+                message: format!("unknown field '{field}'"),
+            }),
+        }
+    }
+
+    fn value(&mut self) -> Result<(String, usize), ParseError> {
+        match self.next() {
+            (Token::Word(value) | Token::Quoted(value), column) => Ok((value, column)),
+            (token, column) => Err(ParseError {
+                column,
+                message: format!("expected a value, found {token}"),
+            }),
+        }
+    }
+
+    fn hrid(&mut self) -> Result<Hrid, ParseError> {
+        let (value, column) = self.value()?;
+        Hrid::try_from(value.as_str()).map_err(|_| ParseError {
+            column,
+            message: format!("invalid HRID '{value}'"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ParseError, Query};
+    use crate::{Hrid, Requirement, Tree};
+
+    fn hrid(s: &str) -> Hrid {
+        Hrid::try_from(s).unwrap()
+    }
+
+    #[test]
+    fn precedence_and_negation() {
+        let query = Query::parse("kind = SYS and not tag:safety or kind != USR").unwrap();
+        assert_eq!(
+            query,
+            Query::Or(
+                Box::new(Query::And(
+                    Box::new(Query::Kind("SYS".to_string())),
+                    Box::new(Query::Not(Box::new(Query::Tag("safety".to_string())))),
+                )),
+                Box::new(Query::Not(Box::new(Query::Kind("USR".to_string())))),
+            )
+        );
+
+        let query = Query::parse("(root or leaf) and descendant_of(USR-002)").unwrap();
+        assert_eq!(
+            query,
+            Query::And(
+                Box::new(Query::Or(Box::new(Query::Root), Box::new(Query::Leaf))),
+                Box::new(Query::DescendantOf(hrid("USR-002"))),
+            )
+        );
+    }
+
+    #[test]
+    fn errors_point_at_the_column() {
+        let error = |query| Query::parse(query).unwrap_err();
+
+        assert_eq!(
+            error("kind = SYS and colour = red"),
+            ParseError {
+                column: 16,
+                message: "unknown field 'colour'".to_string(),
+            }
+        );
+        assert_eq!(error("kind = SYS and").column, 15);
+        assert_eq!(error("descendant_of(nope)").column, 15);
+        assert_eq!(error("(kind = SYS").column, 12);
+        assert_eq!(error("tag:\"unterminated").column, 5);
+        assert_eq!(error("kind = SYS & leaf").column, 12);
+    }
+
+    #[test]
+    fn queries_are_evaluated_against_the_tree() {
+        let mut tree = Tree::default();
+
+        let usr = Requirement::new(hrid("USR-002"), String::new());
+        let mut sys = Requirement::new(hrid("SYS-001"), String::new());
+        sys.add_tag("safety".to_string());
+        let covered = Requirement::new(hrid("SYS-002"), String::new());
+        let tst = Requirement::new(hrid("TST-001"), String::new());
+        let (usr_id, sys_id, covered_id, tst_id) =
+            (usr.uuid(), sys.uuid(), covered.uuid(), tst.uuid());
+
+        for requirement in [usr, sys, covered, tst] {
+            tree.insert(requirement);
+        }
+        tree.link(sys_id, usr_id).unwrap();
+        tree.link(covered_id, usr_id).unwrap();
+        tree.link(tst_id, covered_id).unwrap();
+
+        let evaluate = |query: &str| Query::parse(query).unwrap().evaluate(&tree);
+
+        assert_eq!(
+            evaluate(
+                "kind = SYS and tag:safety and not covered_by(TST) and descendant_of(USR-002)"
+            ),
+            vec![sys_id]
+        );
+        assert_eq!(evaluate("kind = SYS and covered_by(TST)"), vec![covered_id]);
+        assert_eq!(evaluate("ancestor_of(TST-001)"), vec![covered_id, usr_id]);
+        assert_eq!(evaluate("leaf"), vec![sys_id, tst_id]);
+        assert!(evaluate("descendant_of(USR-999)").is_empty());
+    }
+}
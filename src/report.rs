@@ -0,0 +1,15 @@
+//! Reports generated from the graph of requirements
+//!
+//! Reports are read-only views over a [`Tree`](crate::Tree).
+
+mod coverage;
+mod details;
+mod graph;
+mod impact;
+mod matrix;
+
+pub use coverage::{CoverageEntry, CoverageReport};
+pub use details::{Details, LinkDetails, LinkStatus};
+pub use graph::{Clusters, Graph, GraphEdge};
+pub use impact::{ImpactReport, Impacted};
+pub use matrix::{Matrix, MatrixCell, MatrixRow};
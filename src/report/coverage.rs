@@ -0,0 +1,261 @@
+//! Coverage of one kind of requirement by another
+//!
+//! A requirement is 'covered' by a kind if at least one of its children is of
+//! that kind. For example, a user requirement is covered by system requirements
+//! if at least one system requirement links to it as a parent.
+
+use std::{collections::BTreeMap, fmt};
+
+use crate::{Hrid, Requirement, Tree};
+
+/// The coverage of every requirement of one kind by another kind.
+///
+/// Requirements are grouped by namespace. Children in any namespace count
+/// towards coverage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoverageReport {
+    kind: String,
+    covered_by: String,
+    relation: Option<String>,
+    groups: BTreeMap<Vec<String>, Vec<CoverageEntry>>,
+}
+
+/// The coverage of a single requirement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoverageEntry {
+    /// The HRID of the requirement.
+    pub hrid: Hrid,
+
+    /// The HRIDs of the requirement's children of the covering kind.
+    pub covering: Vec<Hrid>,
+}
+
+impl CoverageEntry {
+    /// Whether the requirement has at least one child of the covering kind.
+    #[must_use]
+    pub fn is_covered(&self) -> bool {
+        !self.covering.is_empty()
+    }
+}
+
+impl CoverageReport {
+    /// Builds a coverage report of requirements of `kind` by requirements of
+    /// `covered_by`.
+    ///
+    /// Only requirements whose namespace begins with `namespace` are included.
+    /// If a `relation` is given, only links of that type count towards
+    /// coverage.
+    #[must_use]
+    pub fn new(
+        tree: &Tree,
+        kind: &str,
+        covered_by: &str,
+        namespace: &[String],
+        relation: Option<&str>,
+    ) -> Self {
+        let mut groups: BTreeMap<Vec<String>, Vec<CoverageEntry>> = BTreeMap::new();
+
+        let requirements = tree.requirements().filter(|requirement| {
+            let hrid = requirement.hrid();
+            hrid.kind() == kind && hrid.namespace_starts_with(namespace)
+        });
+
+        for requirement in requirements {
+            let uuid = requirement.uuid();
+            let mut covering: Vec<Hrid> = tree
+                .children(uuid)
+                .filter_map(|child| tree.requirement(child))
+                .filter(|child| {
+                    relation.is_none_or(|relation| {
+                        child
+                            .parent(uuid)
+                            .is_some_and(|link| link.relation.as_deref() == Some(relation))
+                    })
+                })
+                .map(Requirement::hrid)
+                .filter(|child| child.kind() == covered_by)
+                .cloned()
+                .collect();
+            covering.sort();
+
+            let hrid = requirement.hrid();
+            let namespace = hrid.namespace().into_iter().map(str::to_string).collect();
+
+            groups.entry(namespace).or_default().push(CoverageEntry {
+                hrid: hrid.clone(),
+                covering,
+            });
+        }
+
+        for entries in groups.values_mut() {
+            entries.sort_by(|a, b| a.hrid.cmp(&b.hrid));
+        }
+
+        Self {
+            kind: kind.to_string(),
+            covered_by: covered_by.to_string(),
+            relation: relation.map(ToString::to_string),
+            groups,
+        }
+    }
+
+    /// The kind of requirement being covered.
+    #[must_use]
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    /// The kind of requirement providing the coverage.
+    #[must_use]
+    pub fn covered_by(&self) -> &str {
+        &self.covered_by
+    }
+
+    /// The type of relationship which links must have to count towards
+    /// coverage, if any.
+    #[must_use]
+    pub fn relation(&self) -> Option<&str> {
+        self.relation.as_deref()
+    }
+
+    /// Returns an iterator over every entry in the report, grouped by
+    /// namespace.
+    pub fn entries(&self) -> impl Iterator<Item = &CoverageEntry> {
+        self.groups.values().flatten()
+    }
+
+    /// Returns an iterator over the entries which are not covered.
+    pub fn uncovered(&self) -> impl Iterator<Item = &CoverageEntry> {
+        self.entries().filter(|entry| !entry.is_covered())
+    }
+
+    /// The number of requirements in the report.
+    #[must_use]
+    pub fn total(&self) -> usize {
+        self.groups.values().map(Vec::len).sum()
+    }
+
+    /// The number of requirements which are covered.
+    #[must_use]
+    pub fn covered(&self) -> usize {
+        self.entries().filter(|entry| entry.is_covered()).count()
+    }
+}
+
+fn write_summary(f: &mut fmt::Formatter<'_>, entries: &[&CoverageEntry]) -> fmt::Result {
+    let total = entries.len();
+    let covered = entries.iter().filter(|entry| entry.is_covered()).count();
+
+    write!(f, "{covered}/{total}")?;
+    if total > 0 {
+        #[expect(
+            clippy::cast_precision_loss,
+            reason = "requirement counts are far too small to lose precision"
+        )]
+        let percentage = 100.0 * covered as f64 / total as f64;
+        write!(f, " ({percentage:.1}%)")?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for CoverageReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (namespace, entries) in &self.groups {
+            for segment in namespace {
+                write!(f, "{segment}-")?;
+            }
+            write!(f, "{} covered by {}", self.kind, self.covered_by)?;
+            if let Some(relation) = &self.relation {
+                write!(f, " ({relation})")?;
+            }
+            write!(f, ": ")?;
+            write_summary(f, &entries.iter().collect::<Vec<_>>())?;
+            writeln!(f)?;
+
+            for entry in entries {
+                if entry.is_covered() {
+                    let covering: Vec<_> = entry.covering.iter().map(Hrid::to_string).collect();
+                    writeln!(f, "  {}: {}", entry.hrid, covering.join(", "))?;
+                } else {
+                    writeln!(f, "  {}: not covered", entry.hrid)?;
+                }
+            }
+        }
+
+        write!(f, "Total: ")?;
+        write_summary(f, &self.entries().collect::<Vec<_>>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CoverageReport;
+    use crate::{Hrid, Requirement, Tree};
+
+    fn tree() -> Tree {
+        let mut tree = Tree::default();
+
+        let usr_1 = Requirement::new(Hrid::try_from("USR-001").unwrap(), String::new());
+        let usr_2 = Requirement::new(Hrid::try_from("USR-002").unwrap(), String::new());
+        let ns_usr = Requirement::new(Hrid::try_from("NS-USR-001").unwrap(), String::new());
+        let sys = Requirement::new(Hrid::try_from("SYS-001").unwrap(), String::new());
+        let tst = Requirement::new(Hrid::try_from("TST-001").unwrap(), String::new());
+
+        let (usr_1_id, usr_2_id, ns_usr_id) = (usr_1.uuid(), usr_2.uuid(), ns_usr.uuid());
+        let (sys_id, tst_id) = (sys.uuid(), tst.uuid());
+
+        for requirement in [usr_1, usr_2, ns_usr, sys, tst] {
+            tree.insert(requirement);
+        }
+
+        tree.link(sys_id, usr_1_id).unwrap();
+        tree.link_as(sys_id, ns_usr_id, Some("verifies".to_string()))
+            .unwrap();
+        tree.link(tst_id, usr_2_id).unwrap();
+
+        tree
+    }
+
+    #[test]
+    fn only_children_of_the_covering_kind_count() {
+        let report = CoverageReport::new(&tree(), "USR", "SYS", &[], None);
+
+        assert_eq!(report.total(), 3);
+        assert_eq!(report.covered(), 2);
+
+        let uncovered: Vec<_> = report
+            .uncovered()
+            .map(|entry| entry.hrid.to_string())
+            .collect();
+        assert_eq!(uncovered, vec!["USR-002"]);
+    }
+
+    #[test]
+    fn report_is_grouped_by_namespace() {
+        let report = CoverageReport::new(&tree(), "USR", "SYS", &[], None);
+
+        assert_eq!(
+            report.to_string(),
+            "USR covered by SYS: 1/2 (50.0%)
+  USR-001: SYS-001
+  USR-002: not covered
+NS-USR covered by SYS: 1/1 (100.0%)
+  NS-USR-001: SYS-001
+Total: 2/3 (66.7%)"
+        );
+
+        let namespaced = CoverageReport::new(&tree(), "USR", "SYS", &["NS".to_string()], None);
+        assert_eq!(namespaced.total(), 1);
+    }
+
+    #[test]
+    fn only_links_of_the_relation_count() {
+        let report = CoverageReport::new(&tree(), "USR", "SYS", &[], Some("verifies"));
+
+        assert_eq!(report.total(), 3);
+        assert_eq!(report.covered(), 1);
+        assert!(report
+            .to_string()
+            .starts_with("USR covered by SYS (verifies): 0/2 (0.0%)\n  USR-001: not covered"));
+    }
+}
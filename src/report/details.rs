@@ -0,0 +1,276 @@
+//! A detailed view of a single requirement
+//!
+//! This gathers everything known about a requirement, including the state of
+//! the links to its parents and from its children.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    path::PathBuf,
+};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{AttributeValue, Hrid, Tree};
+
+/// Everything known about a single requirement.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Details {
+    /// The HRID of the requirement.
+    pub hrid: Hrid,
+
+    /// The UUID of the requirement.
+    pub uuid: Uuid,
+
+    /// When the requirement was created.
+    pub created: DateTime<Utc>,
+
+    /// The tags on the requirement.
+    pub tags: BTreeSet<String>,
+
+    /// The requirement's current fingerprint.
+    pub fingerprint: String,
+
+    /// The file the requirement is stored in.
+    pub path: PathBuf,
+
+    /// The custom attributes of the requirement, including defaults.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub attributes: BTreeMap<String, AttributeValue>,
+
+    /// The requirement's parents, in HRID order.
+    pub parents: Vec<LinkDetails>,
+
+    /// The requirement's direct children, in HRID order.
+    pub children: Vec<LinkDetails>,
+
+    /// The body of the requirement.
+    pub content: String,
+}
+
+/// A requirement at the other end of a link.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LinkDetails {
+    /// The HRID of the linked requirement.
+    ///
+    /// For a missing parent, this is the HRID recorded in the link.
+    pub hrid: Hrid,
+
+    /// The UUID of the linked requirement.
+    pub uuid: Uuid,
+
+    /// The state of the link.
+    pub status: LinkStatus,
+
+    /// The type of relationship between the requirements, if the link has
+    /// one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub relation: Option<String>,
+}
+
+/// The state of a link between a child and a parent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkStatus {
+    /// The parent has not changed since the link was last reviewed.
+    Current,
+
+    /// The parent has changed since the link was last reviewed.
+    Suspect,
+
+    /// The parent is not in the tree.
+    Missing,
+}
+
+impl Details {
+    /// Gathers the details of the requirement with the given UUID.
+    ///
+    /// `path` is the file the requirement is stored in. Returns `None` if the
+    /// requirement is not in the tree.
+    #[must_use]
+    pub fn new(tree: &Tree, uuid: Uuid, path: PathBuf) -> Option<Self> {
+        let requirement = tree.requirement(uuid)?;
+
+        let mut parents: Vec<LinkDetails> = requirement
+            .parents()
+            .map(|(parent_id, parent)| {
+                let (hrid, status) = tree.requirement(parent_id).map_or_else(
+                    || (parent.hrid.clone(), LinkStatus::Missing),
+                    |current| {
+                        (
+                            current.hrid().clone(),
+                            link_status(tree.is_suspect(uuid, parent_id)),
+                        )
+                    },
+                );
+                LinkDetails {
+                    hrid,
+                    uuid: parent_id,
+                    status,
+                    relation: parent.relation.clone(),
+                }
+            })
+            .collect();
+        parents.sort_by(|a, b| a.hrid.cmp(&b.hrid));
+
+        let mut children: Vec<LinkDetails> = tree
+            .children(uuid)
+            .filter_map(|child_id| {
+                let child = tree.requirement(child_id)?;
+                let link = child.parent(uuid)?;
+                Some(LinkDetails {
+                    hrid: child.hrid().clone(),
+                    uuid: child_id,
+                    status: link_status(tree.is_suspect(child_id, uuid)),
+                    relation: link.relation.clone(),
+                })
+            })
+            .collect();
+        children.sort_by(|a, b| a.hrid.cmp(&b.hrid));
+
+        Some(Self {
+            hrid: requirement.hrid().clone(),
+            uuid,
+            created: requirement.created(),
+            tags: requirement.tags().clone(),
+            fingerprint: requirement.fingerprint(),
+            path,
+            attributes: requirement
+                .attributes()
+                .map(|(name, value)| (name.to_string(), value.clone()))
+                .collect(),
+            parents,
+            children,
+            content: requirement.content().to_string(),
+        })
+    }
+}
+
+const fn link_status(suspect: Option<bool>) -> LinkStatus {
+    match suspect {
+        Some(true) => LinkStatus::Suspect,
+        Some(false) => LinkStatus::Current,
+        None => LinkStatus::Missing,
+    }
+}
+
+impl fmt::Display for Details {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tags: Vec<&str> = self.tags.iter().map(String::as_str).collect();
+
+        writeln!(f, "HRID:        {}", self.hrid)?;
+        writeln!(f, "UUID:        {}", self.uuid)?;
+        writeln!(f, "Created:     {}", self.created.to_rfc3339())?;
+        writeln!(f, "Tags:        {}", tags.join(", "))?;
+        writeln!(f, "Fingerprint: {}", self.fingerprint)?;
+        writeln!(f, "Path:        {}", self.path.display())?;
+
+        if !self.attributes.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "Attributes:")?;
+            for (name, value) in &self.attributes {
+                writeln!(f, "  {name}: {value}")?;
+            }
+        }
+
+        for (heading, links) in [("Parents", &self.parents), ("Children", &self.children)] {
+            writeln!(f)?;
+            writeln!(f, "{heading}:")?;
+            if links.is_empty() {
+                writeln!(f, "  (none)")?;
+            }
+            for link in links {
+                let notes: Vec<&str> = link
+                    .relation
+                    .as_deref()
+                    .into_iter()
+                    .chain(match link.status {
+                        LinkStatus::Current => None,
+                        LinkStatus::Suspect => Some("suspect"),
+                        LinkStatus::Missing => Some("missing"),
+                    })
+                    .collect();
+                if notes.is_empty() {
+                    writeln!(f, "  {}", link.hrid)?;
+                } else {
+                    writeln!(f, "  {} ({})", link.hrid, notes.join(", "))?;
+                }
+            }
+        }
+
+        writeln!(f)?;
+        write!(f, "{}", self.content.trim_start_matches('\n'))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use uuid::Uuid;
+
+    use super::{Details, LinkStatus};
+    use crate::{domain::requirement::Parent, Hrid, Requirement, Tree};
+
+    #[test]
+    fn links_report_their_status() {
+        let mut tree = Tree::default();
+
+        let mut sys = Requirement::new(Hrid::try_from("SYS-001").unwrap(), "body".to_string());
+        sys.add_parent(
+            Uuid::new_v4(),
+            Parent {
+                hrid: Hrid::try_from("USR-001").unwrap(),
+                fingerprint: String::new(),
+                relation: None,
+            },
+        );
+        let mut tst = Requirement::new(Hrid::try_from("TST-001").unwrap(), String::new());
+        tst.add_parent(
+            sys.uuid(),
+            Parent {
+                hrid: sys.hrid().clone(),
+                fingerprint: "stale".to_string(),
+                relation: Some("verifies".to_string()),
+            },
+        );
+        let tst_current = Requirement::new(Hrid::try_from("TST-002").unwrap(), String::new());
+        let sys_id = sys.uuid();
+
+        tree.insert(sys);
+        tree.insert(tst);
+        tree.insert(tst_current);
+        tree.link(
+            tree.find_by_hrid(&Hrid::try_from("TST-002").unwrap())
+                .unwrap()
+                .uuid(),
+            sys_id,
+        )
+        .unwrap();
+
+        let details = Details::new(&tree, sys_id, PathBuf::from("SYS-001.md")).unwrap();
+
+        assert_eq!(details.content, "body");
+        assert_eq!(details.parents.len(), 1);
+        assert_eq!(details.parents[0].hrid, Hrid::try_from("USR-001").unwrap());
+        assert_eq!(details.parents[0].status, LinkStatus::Missing);
+
+        let children: Vec<_> = details
+            .children
+            .iter()
+            .map(|child| (child.hrid.to_string(), child.status))
+            .collect();
+        assert_eq!(
+            children,
+            vec![
+                ("TST-001".to_string(), LinkStatus::Suspect),
+                ("TST-002".to_string(), LinkStatus::Current),
+            ]
+        );
+        assert!(details
+            .to_string()
+            .contains("\n  TST-001 (verifies, suspect)\n"));
+    }
+}
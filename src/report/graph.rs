@@ -0,0 +1,411 @@
+//! Graph exports
+//!
+//! The graph of requirements can be exported in [Graphviz](https://graphviz.org/)
+//! DOT syntax, or as a [Mermaid](https://mermaid.js.org/) flowchart. Mermaid
+//! flowcharts can be rendered inline by both mdBook and Sphinx (using `MyST`).
+
+use std::{
+    collections::{BTreeMap, HashSet, VecDeque},
+    fmt::Write,
+};
+
+use uuid::Uuid;
+
+use crate::{Hrid, Tree};
+
+/// A graph of requirements, ready to be exported.
+///
+/// Edges point from parent to child, so that higher-level requirements are
+/// drawn above the requirements which trace to them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Graph {
+    nodes: Vec<Hrid>,
+    edges: Vec<GraphEdge>,
+}
+
+/// A link between two requirements in a [`Graph`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphEdge {
+    /// The HRID of the parent requirement.
+    pub parent: Hrid,
+
+    /// The HRID of the child requirement.
+    pub child: Hrid,
+
+    /// Whether the link is suspect.
+    pub suspect: bool,
+
+    /// The type of relationship between the requirements, if the link has
+    /// one.
+    pub relation: Option<String>,
+}
+
+impl GraphEdge {
+    /// The label drawn on the edge, if it has a relation or is suspect.
+    fn label(&self) -> Option<String> {
+        match (&self.relation, self.suspect) {
+            (Some(relation), true) => Some(format!("{relation}, suspect")),
+            (Some(relation), false) => Some(relation.clone()),
+            (None, true) => Some("suspect".to_string()),
+            (None, false) => None,
+        }
+    }
+}
+
+/// How to group the nodes of a [`Graph`] when it is exported.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Clusters {
+    /// Don't group the nodes.
+    #[default]
+    None,
+
+    /// Group nodes by requirement kind.
+    Kind,
+
+    /// Group nodes by namespace.
+    Namespace,
+}
+
+impl Graph {
+    /// Builds a graph of every requirement in the tree.
+    #[must_use]
+    pub fn new(tree: &Tree) -> Self {
+        Self::from_nodes(
+            tree,
+            &tree.requirements().map(crate::Requirement::uuid).collect(),
+        )
+    }
+
+    /// Builds the subgraph around a single requirement.
+    ///
+    /// The subgraph includes ancestors up to `up` links above the requirement,
+    /// and descendants up to `down` links below it. A limit of `None` includes
+    /// every ancestor or descendant.
+    #[must_use]
+    pub fn around(tree: &Tree, uuid: Uuid, up: Option<usize>, down: Option<usize>) -> Self {
+        let mut nodes = reachable(uuid, up, |uuid| tree.parents(uuid).collect());
+        nodes.extend(reachable(uuid, down, |uuid| tree.children(uuid).collect()));
+        Self::from_nodes(tree, &nodes)
+    }
+
+    fn from_nodes(tree: &Tree, nodes: &HashSet<Uuid>) -> Self {
+        let suspect_links: HashSet<(Uuid, Uuid)> = tree
+            .suspect_links()
+            .into_iter()
+            .map(|link| (link.child, link.parent))
+            .collect();
+
+        let mut edges = Vec::new();
+        let mut hrids = Vec::with_capacity(nodes.len());
+
+        for &uuid in nodes {
+            let Some(child) = tree.requirement(uuid) else {
+                continue;
+            };
+            hrids.push(child.hrid().clone());
+
+            for (parent, link) in child.parents().filter(|(parent, _)| nodes.contains(parent)) {
+                if let Some(parent_requirement) = tree.requirement(parent) {
+                    edges.push(GraphEdge {
+                        parent: parent_requirement.hrid().clone(),
+                        child: child.hrid().clone(),
+                        suspect: suspect_links.contains(&(uuid, parent)),
+                        relation: link.relation.clone(),
+                    });
+                }
+            }
+        }
+
+        hrids.sort();
+        edges.sort_by(|a, b| (&a.parent, &a.child).cmp(&(&b.parent, &b.child)));
+
+        Self {
+            nodes: hrids,
+            edges,
+        }
+    }
+
+    /// Removes the requirements for which `keep` returns `false`, along with
+    /// their links.
+    pub fn retain(&mut self, keep: impl Fn(&Hrid) -> bool) {
+        self.nodes.retain(&keep);
+        self.edges
+            .retain(|edge| keep(&edge.parent) && keep(&edge.child));
+    }
+
+    /// Removes the links for which `keep` returns `false`.
+    ///
+    /// The requirements they link are kept.
+    pub fn retain_edges(&mut self, keep: impl Fn(&GraphEdge) -> bool) {
+        self.edges.retain(keep);
+    }
+
+    /// The HRIDs of the requirements in the graph, in order.
+    #[must_use]
+    pub fn nodes(&self) -> &[Hrid] {
+        &self.nodes
+    }
+
+    /// The links between requirements in the graph.
+    #[must_use]
+    pub fn edges(&self) -> &[GraphEdge] {
+        &self.edges
+    }
+
+    /// Exports the graph in Graphviz DOT syntax.
+    #[must_use]
+    pub fn to_dot(&self, clusters: Clusters) -> String {
+        let mut output = String::from("digraph requirements {\n    node [shape=box];\n");
+
+        for (index, (label, nodes)) in self.clusters(clusters).into_iter().enumerate() {
+            if let Some(label) = &label {
+                let _ = writeln!(output, "    subgraph cluster_{index} {{");
+                let _ = writeln!(output, "        label=\"{label}\";");
+            }
+            let indent = if label.is_some() { "        " } else { "    " };
+
+            for node in nodes {
+                let _ = writeln!(output, "{indent}\"{node}\";");
+            }
+
+            if label.is_some() {
+                output.push_str("    }\n");
+            }
+        }
+
+        for edge in &self.edges {
+            let style = match (edge.label(), edge.suspect) {
+                (Some(label), true) => format!(
+                    " [style=dashed, color=red, label=\"{}\"]",
+                    escape_dot(&label)
+                ),
+                (Some(label), false) => format!(" [label=\"{}\"]", escape_dot(&label)),
+                (None, _) => String::new(),
+            };
+            let _ = writeln!(
+                output,
+                "    \"{}\" -> \"{}\"{style};",
+                edge.parent, edge.child
+            );
+        }
+
+        output.push_str("}\n");
+        output
+    }
+
+    /// Exports the graph as a Mermaid flowchart.
+    #[must_use]
+    pub fn to_mermaid(&self, clusters: Clusters) -> String {
+        // Mermaid node IDs can't safely contain dashes, so nodes are given
+        // positional IDs and labelled with their HRIDs.
+        let ids: BTreeMap<&Hrid, usize> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(index, hrid)| (hrid, index))
+            .collect();
+
+        let mut output = String::from("flowchart TD\n");
+
+        for (index, (label, nodes)) in self.clusters(clusters).into_iter().enumerate() {
+            if let Some(label) = &label {
+                let _ = writeln!(output, "    subgraph cluster_{index} [\"{label}\"]");
+            }
+            let indent = if label.is_some() { "        " } else { "    " };
+
+            for node in nodes {
+                let _ = writeln!(output, "{indent}n{}[\"{node}\"]", ids[node]);
+            }
+
+            if label.is_some() {
+                output.push_str("    end\n");
+            }
+        }
+
+        for edge in &self.edges {
+            let arrow = match (edge.label(), edge.suspect) {
+                (Some(label), true) => format!("-.->|\"{}\"|", escape_mermaid(&label)),
+                (Some(label), false) => format!("-->|\"{}\"|", escape_mermaid(&label)),
+                (None, _) => "-->".to_string(),
+            };
+            let _ = writeln!(
+                output,
+                "    n{} {arrow} n{}",
+                ids[&edge.parent], ids[&edge.child]
+            );
+        }
+
+        output
+    }
+
+    /// Groups the nodes into labelled clusters.
+    ///
+    /// If nodes are not clustered, a single unlabelled group is returned.
+    fn clusters(&self, clusters: Clusters) -> Vec<(Option<String>, Vec<&Hrid>)> {
+        let key = |hrid: &Hrid| match clusters {
+            Clusters::None => None,
+            Clusters::Kind => Some(hrid.kind().to_string()),
+            Clusters::Namespace => {
+                let namespace = hrid.namespace();
+                if namespace.is_empty() {
+                    Some("(no namespace)".to_string())
+                } else {
+                    Some(namespace.join("-"))
+                }
+            }
+        };
+
+        let mut groups: BTreeMap<Option<String>, Vec<&Hrid>> = BTreeMap::new();
+        for hrid in &self.nodes {
+            groups.entry(key(hrid)).or_default().push(hrid);
+        }
+        groups.into_iter().collect()
+    }
+}
+
+/// Finds the requirements reachable from `start` within `limit` steps.
+///
+/// The starting requirement is always included.
+fn reachable(start: Uuid, limit: Option<usize>, next: impl Fn(Uuid) -> Vec<Uuid>) -> HashSet<Uuid> {
+    let mut visited = HashSet::from([start]);
+    let mut queue = VecDeque::from([(start, 0)]);
+
+    while let Some((uuid, depth)) = queue.pop_front() {
+        if limit.is_some_and(|limit| depth >= limit) {
+            continue;
+        }
+        for neighbour in next(uuid) {
+            if visited.insert(neighbour) {
+                queue.push_back((neighbour, depth + 1));
+            }
+        }
+    }
+
+    visited
+}
+
+/// Escapes text for use in a quoted DOT string.
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Escapes text for use in a quoted Mermaid label.
+fn escape_mermaid(text: &str) -> String {
+    text.replace('"', "#quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Clusters, Graph};
+    use crate::{domain::requirement::Parent, Hrid, Requirement, Tree};
+
+    fn tree() -> (Tree, uuid::Uuid) {
+        let mut tree = Tree::default();
+
+        let usr = Requirement::new(Hrid::try_from("USR-001").unwrap(), String::new());
+        let sys = Requirement::new(Hrid::try_from("SYS-001").unwrap(), String::new());
+        let mut tst = Requirement::new(Hrid::try_from("TST-001").unwrap(), String::new());
+        tst.add_parent(
+            sys.uuid(),
+            Parent {
+                hrid: sys.hrid().clone(),
+                fingerprint: "outdated".to_string(),
+                relation: None,
+            },
+        );
+
+        let (usr_id, sys_id) = (usr.uuid(), sys.uuid());
+        for requirement in [usr, sys, tst] {
+            tree.insert(requirement);
+        }
+        tree.link_as(sys_id, usr_id, Some("satisfies".to_string()))
+            .unwrap();
+
+        (tree, sys_id)
+    }
+
+    #[test]
+    fn dot_export() {
+        let (tree, _) = tree();
+
+        assert_eq!(
+            Graph::new(&tree).to_dot(Clusters::None),
+            r#"digraph requirements {
+    node [shape=box];
+    "SYS-001";
+    "TST-001";
+    "USR-001";
+    "SYS-001" -> "TST-001" [style=dashed, color=red, label="suspect"];
+    "USR-001" -> "SYS-001" [label="satisfies"];
+}
+"#
+        );
+    }
+
+    #[test]
+    fn mermaid_export_with_clusters() {
+        let (tree, _) = tree();
+
+        assert_eq!(
+            Graph::new(&tree).to_mermaid(Clusters::Kind),
+            r#"flowchart TD
+    subgraph cluster_0 ["SYS"]
+        n0["SYS-001"]
+    end
+    subgraph cluster_1 ["TST"]
+        n1["TST-001"]
+    end
+    subgraph cluster_2 ["USR"]
+        n2["USR-001"]
+    end
+    n0 -.->|"suspect"| n1
+    n2 -->|"satisfies"| n0
+"#
+        );
+    }
+
+    #[test]
+    fn labels_are_escaped() {
+        let (mut tree, sys) = tree();
+        let usr = tree
+            .find_by_hrid(&Hrid::try_from("USR-001").unwrap())
+            .unwrap()
+            .uuid();
+        tree.link_as(sys, usr, Some(r#"is "like" \ -->"#.to_string()))
+            .unwrap();
+        let graph = Graph::new(&tree);
+
+        assert!(graph
+            .to_dot(Clusters::None)
+            .contains(r#""USR-001" -> "SYS-001" [label="is \"like\" \\ -->"];"#));
+        assert!(graph
+            .to_mermaid(Clusters::None)
+            .contains(r#"n2 -->|"is #quot;like#quot; \ -->"| n0"#));
+    }
+
+    #[test]
+    fn subgraph_around_a_requirement() {
+        let (tree, sys) = tree();
+
+        let down = Graph::around(&tree, sys, Some(0), None);
+        let nodes: Vec<_> = down.nodes().iter().map(Hrid::to_string).collect();
+        assert_eq!(nodes, vec!["SYS-001", "TST-001"]);
+        assert_eq!(down.edges().len(), 1);
+
+        let up = Graph::around(&tree, sys, None, Some(0));
+        let nodes: Vec<_> = up.nodes().iter().map(Hrid::to_string).collect();
+        assert_eq!(nodes, vec!["SYS-001", "USR-001"]);
+    }
+
+    #[test]
+    fn edges_can_be_filtered_by_relation() {
+        let (tree, _) = tree();
+
+        let mut graph = Graph::new(&tree);
+        graph.retain_edges(|edge| edge.relation.as_deref() == Some("satisfies"));
+
+        assert_eq!(graph.nodes().len(), 3);
+        assert_eq!(graph.edges().len(), 1);
+        assert_eq!(graph.edges()[0].child.to_string(), "SYS-001");
+    }
+}
@@ -0,0 +1,167 @@
+//! Change impact analysis
+//!
+//! When a requirement changes, every requirement which traces to it, directly
+//! or indirectly, may need to be reviewed.
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    fmt,
+};
+
+use uuid::Uuid;
+
+use crate::{Hrid, Tree};
+
+/// The requirements which would need review if a set of requirements changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImpactReport {
+    impacted: Vec<Impacted>,
+}
+
+/// A requirement which would need review.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Impacted {
+    /// The HRID of the requirement.
+    pub hrid: Hrid,
+
+    /// A shortest chain of links by which the requirement is reached.
+    ///
+    /// The path starts at the nearest changed requirement and ends at this
+    /// requirement.
+    pub path: Vec<Hrid>,
+}
+
+impl ImpactReport {
+    /// Finds every descendant of the changed requirements.
+    ///
+    /// A changed requirement is only included if it is also a descendant of
+    /// another changed requirement.
+    #[must_use]
+    pub fn new(tree: &Tree, changed: &[Uuid]) -> Self {
+        let starts: HashSet<Uuid> = changed.iter().copied().collect();
+        let mut predecessors: HashMap<Uuid, Uuid> = HashMap::new();
+        let mut visited: HashSet<Uuid> = HashSet::new();
+        let mut queue: VecDeque<Uuid> = changed.iter().copied().collect();
+        let mut order = Vec::new();
+
+        while let Some(uuid) = queue.pop_front() {
+            for child in tree.children(uuid) {
+                if tree.requirement(child).is_some() && visited.insert(child) {
+                    predecessors.insert(child, uuid);
+                    queue.push_back(child);
+                    order.push(child);
+                }
+            }
+        }
+
+        let hrid = |uuid| tree.requirement(uuid).map(|r| r.hrid().clone());
+
+        let mut impacted: Vec<Impacted> = order
+            .into_iter()
+            .filter_map(|uuid| {
+                // Follow the links back to the nearest changed requirement
+                let mut path = vec![uuid];
+                let mut node = uuid;
+                while let Some(&previous) = predecessors.get(&node) {
+                    path.push(previous);
+                    if starts.contains(&previous) {
+                        break;
+                    }
+                    node = previous;
+                }
+                path.reverse();
+
+                Some(Impacted {
+                    hrid: hrid(uuid)?,
+                    path: path.into_iter().filter_map(hrid).collect(),
+                })
+            })
+            .collect();
+
+        impacted.sort_by(|a, b| a.hrid.cmp(&b.hrid));
+
+        Self { impacted }
+    }
+
+    /// The impacted requirements, in HRID order.
+    #[must_use]
+    pub fn impacted(&self) -> &[Impacted] {
+        &self.impacted
+    }
+
+    /// The impacted requirements, grouped by kind.
+    #[must_use]
+    pub fn by_kind(&self) -> BTreeMap<&str, Vec<&Impacted>> {
+        let mut groups: BTreeMap<&str, Vec<&Impacted>> = BTreeMap::new();
+        for impacted in &self.impacted {
+            groups
+                .entry(impacted.hrid.kind())
+                .or_default()
+                .push(impacted);
+        }
+        groups
+    }
+}
+
+impl fmt::Display for ImpactReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (kind, impacted) in self.by_kind() {
+            writeln!(f, "{kind}:")?;
+            for requirement in impacted {
+                let path: Vec<_> = requirement.path.iter().map(Hrid::to_string).collect();
+                writeln!(f, "  {} (via {})", requirement.hrid, path.join(" -> "))?;
+            }
+        }
+        write!(f, "{} requirement(s) need review", self.impacted.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ImpactReport;
+    use crate::{Hrid, Requirement, Tree};
+
+    #[test]
+    fn descendants_are_reported_with_paths() {
+        let mut tree = Tree::default();
+
+        let usr = Requirement::new(Hrid::try_from("USR-001").unwrap(), String::new());
+        let sys = Requirement::new(Hrid::try_from("SYS-001").unwrap(), String::new());
+        let tst = Requirement::new(Hrid::try_from("TST-001").unwrap(), String::new());
+        let other = Requirement::new(Hrid::try_from("USR-002").unwrap(), String::new());
+
+        let (usr_id, sys_id, tst_id) = (usr.uuid(), sys.uuid(), tst.uuid());
+        for requirement in [usr, sys, tst, other] {
+            tree.insert(requirement);
+        }
+        tree.link(sys_id, usr_id).unwrap();
+        tree.link(tst_id, sys_id).unwrap();
+
+        let report = ImpactReport::new(&tree, &[usr_id]);
+
+        assert_eq!(
+            report.to_string(),
+            "SYS:
+  SYS-001 (via USR-001 -> SYS-001)
+TST:
+  TST-001 (via USR-001 -> SYS-001 -> TST-001)
+2 requirement(s) need review"
+        );
+
+        assert!(ImpactReport::new(&tree, &[tst_id]).impacted().is_empty());
+
+        // A changed requirement is reported if it descends from another
+        assert_eq!(
+            ImpactReport::new(&tree, &[usr_id, sys_id]).to_string(),
+            "SYS:
+  SYS-001 (via USR-001 -> SYS-001)
+TST:
+  TST-001 (via SYS-001 -> TST-001)
+2 requirement(s) need review"
+        );
+        assert_eq!(
+            ImpactReport::new(&tree, &[sys_id, usr_id]).impacted().len(),
+            2
+        );
+    }
+}
@@ -0,0 +1,359 @@
+//! Traceability matrices
+//!
+//! A traceability matrix lists, for every requirement of one kind, the
+//! requirements of another kind which trace to it.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt::Write,
+};
+
+use uuid::Uuid;
+
+use crate::{Hrid, Tree};
+
+/// A traceability matrix between two kinds of requirement.
+///
+/// Rows are requirements of the row kind. Each row lists the requirements of
+/// the column kind which are descendants of the row requirement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matrix {
+    row_kind: String,
+    column_kind: String,
+    rows: Vec<MatrixRow>,
+}
+
+/// A single row of a [`Matrix`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatrixRow {
+    /// The HRID of the row requirement.
+    pub hrid: Hrid,
+
+    /// The requirements of the column kind which trace to the row requirement.
+    pub cells: Vec<MatrixCell>,
+}
+
+/// A requirement which traces to the requirement in a [`MatrixRow`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatrixCell {
+    /// The HRID of the tracing requirement.
+    pub hrid: Hrid,
+
+    /// Whether any link between the two requirements is suspect.
+    pub suspect: bool,
+}
+
+impl Matrix {
+    /// Builds a traceability matrix from the links in the tree.
+    ///
+    /// Only direct children of the row requirements are included, unless
+    /// intermediate kinds are given in `via`. Links are then followed through
+    /// requirements of those kinds. For example, with rows of 'USR', columns of
+    /// 'TST' and `via` of 'SYS', tests are traced to user requirements through
+    /// system requirements.
+    ///
+    /// If a `relation` is given, only links of that type are followed.
+    ///
+    /// A cell is marked as suspect if any link along any path between the two
+    /// requirements is suspect.
+    #[must_use]
+    pub fn new(
+        tree: &Tree,
+        row_kind: &str,
+        column_kind: &str,
+        via: &[String],
+        relation: Option<&str>,
+    ) -> Self {
+        let suspect_links: HashSet<(Uuid, Uuid)> = tree
+            .suspect_links()
+            .into_iter()
+            .map(|link| (link.child, link.parent))
+            .collect();
+
+        let mut rows: Vec<MatrixRow> = tree
+            .requirements()
+            .filter(|requirement| requirement.hrid().kind() == row_kind)
+            .map(|requirement| {
+                let mut cells: BTreeMap<Hrid, bool> = BTreeMap::new();
+                let mut visited = HashSet::new();
+                let mut stack = vec![(requirement.uuid(), false)];
+
+                while let Some((parent, suspect)) = stack.pop() {
+                    for child in tree.children(parent) {
+                        let Some(child_requirement) = tree.requirement(child) else {
+                            continue;
+                        };
+                        if relation.is_some_and(|relation| {
+                            child_requirement
+                                .parent(parent)
+                                .is_none_or(|link| link.relation.as_deref() != Some(relation))
+                        }) {
+                            continue;
+                        }
+                        let suspect = suspect || suspect_links.contains(&(child, parent));
+                        let kind = child_requirement.hrid().kind();
+
+                        if kind == column_kind {
+                            *cells.entry(child_requirement.hrid().clone()).or_default() |= suspect;
+                        } else if via.iter().any(|via| via == kind)
+                            && visited.insert((child, suspect))
+                        {
+                            stack.push((child, suspect));
+                        }
+                    }
+                }
+
+                MatrixRow {
+                    hrid: requirement.hrid().clone(),
+                    cells: cells
+                        .into_iter()
+                        .map(|(hrid, suspect)| MatrixCell { hrid, suspect })
+                        .collect(),
+                }
+            })
+            .collect();
+
+        rows.sort_by(|a, b| a.hrid.cmp(&b.hrid));
+
+        Self {
+            row_kind: row_kind.to_string(),
+            column_kind: column_kind.to_string(),
+            rows,
+        }
+    }
+
+    /// Removes the rows for which `keep` returns `false`.
+    pub fn retain_rows(&mut self, keep: impl Fn(&Hrid) -> bool) {
+        self.rows.retain(|row| keep(&row.hrid));
+    }
+
+    /// The rows of the matrix, in HRID order.
+    #[must_use]
+    pub fn rows(&self) -> &[MatrixRow] {
+        &self.rows
+    }
+
+    /// Renders the matrix as a Markdown table.
+    #[must_use]
+    pub fn to_markdown(&self) -> String {
+        let mut output = format!(
+            "| {} | {} |\n| --- | --- |\n",
+            self.row_kind, self.column_kind
+        );
+        for row in &self.rows {
+            let _ = writeln!(output, "| {} | {} |", row.hrid, row.cells_text());
+        }
+        output
+    }
+
+    /// Renders the matrix as comma-separated values.
+    ///
+    /// Multiple requirements in a single cell are separated by semicolons.
+    #[must_use]
+    pub fn to_csv(&self) -> String {
+        let mut output = format!(
+            "{},{}\n",
+            csv_field(&self.row_kind),
+            csv_field(&self.column_kind)
+        );
+        for row in &self.rows {
+            let cells: Vec<_> = row.cells.iter().map(MatrixCell::text).collect();
+            let _ = writeln!(
+                output,
+                "{},{}",
+                csv_field(&row.hrid.to_string()),
+                csv_field(&cells.join("; "))
+            );
+        }
+        output
+    }
+
+    /// Renders the matrix as a standalone HTML document.
+    #[must_use]
+    pub fn to_html(&self) -> String {
+        let title = format!(
+            "Traceability matrix: {} to {}",
+            html_escape(&self.row_kind),
+            html_escape(&self.column_kind)
+        );
+
+        let mut output = format!(
+            r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+table {{ border-collapse: collapse; }}
+th, td {{ border: 1px solid #999; padding: 0.25em 0.5em; text-align: left; }}
+.suspect {{ color: #b00; font-weight: bold; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<table>
+<thead>
+<tr><th>{}</th><th>{}</th></tr>
+</thead>
+<tbody>
+"#,
+            html_escape(&self.row_kind),
+            html_escape(&self.column_kind)
+        );
+
+        for row in &self.rows {
+            let cells: Vec<_> = row
+                .cells
+                .iter()
+                .map(|cell| {
+                    let hrid = html_escape(&cell.hrid.to_string());
+                    if cell.suspect {
+                        format!(r#"<span class="suspect">{hrid} (suspect)</span>"#)
+                    } else {
+                        hrid
+                    }
+                })
+                .collect();
+            let _ = writeln!(
+                output,
+                "<tr><td>{}</td><td>{}</td></tr>",
+                html_escape(&row.hrid.to_string()),
+                cells.join(", ")
+            );
+        }
+
+        output.push_str("</tbody>\n</table>\n</body>\n</html>\n");
+        output
+    }
+}
+
+impl MatrixRow {
+    fn cells_text(&self) -> String {
+        let cells: Vec<_> = self.cells.iter().map(MatrixCell::text).collect();
+        cells.join(", ")
+    }
+}
+
+impl MatrixCell {
+    fn text(&self) -> String {
+        if self.suspect {
+            format!("{} (suspect)", self.hrid)
+        } else {
+            self.hrid.to_string()
+        }
+    }
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Matrix;
+    use crate::{Hrid, Requirement, Tree};
+
+    fn tree() -> Tree {
+        let mut tree = Tree::default();
+
+        let usr_1 = Requirement::new(Hrid::try_from("USR-001").unwrap(), String::new());
+        let usr_2 = Requirement::new(Hrid::try_from("USR-002").unwrap(), String::new());
+        let sys = Requirement::new(Hrid::try_from("SYS-001").unwrap(), String::new());
+        let tst = Requirement::new(Hrid::try_from("TST-001").unwrap(), String::new());
+
+        let (usr_1_id, usr_2_id) = (usr_1.uuid(), usr_2.uuid());
+        let (sys_id, tst_id) = (sys.uuid(), tst.uuid());
+
+        for requirement in [usr_1, usr_2, sys, tst] {
+            tree.insert(requirement);
+        }
+
+        tree.link_as(sys_id, usr_1_id, Some("satisfies".to_string()))
+            .unwrap();
+        tree.link(sys_id, usr_2_id).unwrap();
+        tree.link_as(tst_id, sys_id, Some("satisfies".to_string()))
+            .unwrap();
+
+        tree
+    }
+
+    #[test]
+    fn direct_matrix_as_markdown() {
+        let matrix = Matrix::new(&tree(), "USR", "SYS", &[], None);
+
+        assert_eq!(
+            matrix.to_markdown(),
+            "| USR | SYS |
+| --- | --- |
+| USR-001 | SYS-001 |
+| USR-002 | SYS-001 |
+"
+        );
+    }
+
+    #[test]
+    fn transitive_matrix_as_csv() {
+        let direct = Matrix::new(&tree(), "USR", "TST", &[], None);
+        assert!(direct.rows().iter().all(|row| row.cells.is_empty()));
+
+        let transitive = Matrix::new(&tree(), "USR", "TST", &["SYS".to_string()], None);
+        assert_eq!(
+            transitive.to_csv(),
+            "USR,TST
+USR-001,TST-001
+USR-002,TST-001
+"
+        );
+    }
+
+    #[test]
+    fn only_links_of_the_relation_are_followed() {
+        let matrix = Matrix::new(
+            &tree(),
+            "USR",
+            "TST",
+            &["SYS".to_string()],
+            Some("satisfies"),
+        );
+
+        assert_eq!(
+            matrix.to_csv(),
+            "USR,TST
+USR-001,TST-001
+USR-002,
+"
+        );
+    }
+
+    #[test]
+    fn suspect_links_are_marked() {
+        let mut tree = Tree::default();
+        let usr = Requirement::new(Hrid::try_from("USR-001").unwrap(), "original".to_string());
+        let mut sys = Requirement::new(Hrid::try_from("SYS-001").unwrap(), String::new());
+        sys.add_parent(
+            usr.uuid(),
+            crate::domain::requirement::Parent {
+                hrid: usr.hrid().clone(),
+                fingerprint: "outdated".to_string(),
+                relation: None,
+            },
+        );
+        tree.insert(usr);
+        tree.insert(sys);
+
+        let matrix = Matrix::new(&tree, "USR", "SYS", &[], None);
+        assert!(matrix.rows()[0].cells[0].suspect);
+        assert!(matrix.to_html().contains("SYS-001 (suspect)"));
+    }
+}
@@ -0,0 +1,210 @@
+//! Full-text search over the bodies of requirements
+
+use std::ops::Range;
+
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use regex::{Regex, RegexBuilder};
+
+use crate::{Filter, Requirement, Tree};
+
+/// A pattern to search for in the bodies of requirements.
+#[derive(Debug, Clone)]
+pub struct Search {
+    pattern: Regex,
+    context: usize,
+}
+
+/// A requirement whose body matches a [`Search`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchResult<'a> {
+    /// The matching requirement.
+    pub requirement: &'a Requirement,
+
+    /// The lines of the body which match, and the context lines around them,
+    /// in order.
+    pub lines: Vec<MatchedLine>,
+}
+
+/// A line of a requirement body which matches a [`Search`], or is context
+/// around a line which does.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchedLine {
+    /// The line number within the body, starting from 1.
+    pub number: usize,
+
+    /// The text of the line.
+    pub text: String,
+
+    /// The byte ranges of the matches within the line.
+    ///
+    /// This is empty for a context line.
+    pub matches: Vec<Range<usize>>,
+}
+
+impl Search {
+    /// Searches for a literal string, ignoring case.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the escaped literal exceeds the regex size limit.
+    #[must_use]
+    pub fn literal(text: &str) -> Self {
+        let pattern = RegexBuilder::new(&regex::escape(text))
+            .case_insensitive(true)
+            .build()
+            .expect("an escaped literal is a valid regex");
+        Self {
+            pattern,
+            context: 0,
+        }
+    }
+
+    /// Searches for a regular expression.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pattern is not a valid regular expression.
+    pub fn regex(pattern: &str) -> Result<Self, regex::Error> {
+        Ok(Self {
+            pattern: Regex::new(pattern)?,
+            context: 0,
+        })
+    }
+
+    /// Includes up to `lines` lines of context before and after each matching
+    /// line in the results.
+    #[must_use]
+    pub const fn with_context(mut self, lines: usize) -> Self {
+        self.context = lines;
+        self
+    }
+
+    /// Searches the requirements selected by `filter`, in parallel.
+    ///
+    /// Results are in HRID order.
+    #[must_use]
+    pub fn run<'a>(&self, tree: &'a Tree, filter: &Filter) -> Vec<SearchResult<'a>> {
+        let requirements = filter.apply(tree);
+
+        requirements
+            .into_par_iter()
+            .filter_map(|requirement| {
+                let lines = self.matching_lines(requirement.content());
+                (!lines.is_empty()).then_some(SearchResult { requirement, lines })
+            })
+            .collect()
+    }
+
+    fn matching_lines(&self, content: &str) -> Vec<MatchedLine> {
+        let lines: Vec<(&str, Vec<Range<usize>>)> = content
+            .lines()
+            .map(|line| {
+                let matches = self
+                    .pattern
+                    .find_iter(line)
+                    .map(|found| found.range())
+                    .filter(|range| !range.is_empty())
+                    .collect();
+                (line, matches)
+            })
+            .collect();
+
+        let mut included = vec![false; lines.len()];
+        for (index, (_, matches)) in lines.iter().enumerate() {
+            if !matches.is_empty() {
+                let end = (index + self.context).min(lines.len() - 1);
+                included[index.saturating_sub(self.context)..=end].fill(true);
+            }
+        }
+
+        lines
+            .into_iter()
+            .enumerate()
+            .filter(|&(index, _)| included[index])
+            .map(|(index, (line, matches))| MatchedLine {
+                number: index + 1,
+                text: line.to_string(),
+                matches,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Search;
+    use crate::{Filter, Hrid, Requirement, Tree};
+
+    fn tree() -> Tree {
+        let mut tree = Tree::default();
+        tree.insert(Requirement::new(
+            Hrid::try_from("SYS-002").unwrap(),
+            "Overview\nLatency must be low.\nlatency, latency!".to_string(),
+        ));
+        tree.insert(Requirement::new(
+            Hrid::try_from("SYS-001").unwrap(),
+            "The system shall respond.".to_string(),
+        ));
+        tree.insert(Requirement::new(
+            Hrid::try_from("USR-001").unwrap(),
+            "Users dislike latency.".to_string(),
+        ));
+        tree
+    }
+
+    #[test]
+    fn literal_search_ignores_case() {
+        let tree = tree();
+        let results = Search::literal("LATENCY").run(&tree, &Filter::default());
+
+        let hrids: Vec<_> = results
+            .iter()
+            .map(|result| result.requirement.hrid().to_string())
+            .collect();
+        assert_eq!(hrids, vec!["SYS-002", "USR-001"]);
+
+        let lines = &results[0].lines;
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].number, 2);
+        assert_eq!(lines[0].matches, vec![0..7]);
+        assert_eq!(lines[1].matches, vec![0..7, 9..16]);
+    }
+
+    #[test]
+    fn regex_search_respects_filter() {
+        let tree = tree();
+        let filter = Filter {
+            kinds: vec!["SYS".to_string()],
+            ..Filter::default()
+        };
+        let results = Search::regex(r"shall \w+").unwrap().run(&tree, &filter);
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].lines[0].text, "The system shall respond.");
+
+        assert!(Search::regex("(").is_err());
+    }
+
+    #[test]
+    fn context_lines_surround_matches() {
+        let mut tree = Tree::default();
+        tree.insert(Requirement::new(
+            Hrid::try_from("SYS-001").unwrap(),
+            "one\ntwo\nlatency\nthree\nfour\nfive\nlatency".to_string(),
+        ));
+
+        let results = Search::literal("latency")
+            .with_context(1)
+            .run(&tree, &Filter::default());
+
+        let lines: Vec<_> = results[0]
+            .lines
+            .iter()
+            .map(|line| (line.number, line.matches.is_empty()))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![(2, true), (3, false), (4, true), (6, true), (7, false)]
+        );
+    }
+}
@@ -1,5 +1,8 @@
 mod directory;
+mod git;
+mod init;
 mod tree;
 
 pub use directory::Directory;
-pub use tree::Tree;
+pub use init::Integration;
+pub use tree::{BrokenLink, BrokenLinkKind, Cycle, DeletePolicy, SuspectLink, Tree};
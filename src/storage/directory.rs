@@ -5,6 +5,7 @@
 //! [`Tree`].
 
 use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     ffi::OsStr,
     fmt::{self},
     io,
@@ -14,19 +15,32 @@ use std::{
 
 use nonempty::NonEmpty;
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use uuid::Uuid;
 use walkdir::WalkDir;
 
 pub use crate::storage::Tree;
 use crate::{
     domain::{
-        requirement::{LoadError, Parent},
-        Config, Hrid,
+        requirement::LoadError, AttributeError, AttributeValue, Config, ConfigError,
+        DisallowedKindError, DisallowedParentError, Hrid, SchemaError, UnknownRelationError,
+    },
+    storage::{
+        git::{self, GitError},
+        init::{self, InitError, Integration},
+        tree::{BrokenLink, Cycle, DeleteError, LinkError, RenameError, ReplaceError, SuspectLink},
+        DeletePolicy,
     },
     EmptyStringError, Requirement,
 };
 
 #[derive(Debug, PartialEq)]
-pub struct Loaded(Tree);
+pub struct Loaded {
+    tree: Tree,
+    config: Config,
+
+    /// The path of the file each requirement was loaded from, by UUID.
+    paths: HashMap<Uuid, PathBuf>,
+}
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct Unloaded;
@@ -38,38 +52,6 @@ pub struct Directory<S> {
     state: S,
 }
 
-impl<S> Directory<S> {
-    /// Link two requirements together with a parent-child relationship.
-    ///
-    /// # Errors
-    ///
-    /// This method can fail if:
-    ///
-    /// - either the child or parent requirement file cannot be found
-    /// - either the child or parent requirement file cannot be parsed
-    /// - the child requirement file cannot be written to
-    pub fn link_requirement(&self, child: Hrid, parent: Hrid) -> Result<Requirement, LoadError> {
-        let mut child = self.load_requirement(child)?;
-        let parent = self.load_requirement(parent)?;
-
-        child.add_parent(
-            parent.uuid(),
-            Parent {
-                hrid: parent.hrid().clone(),
-                fingerprint: parent.fingerprint(),
-            },
-        );
-
-        child.save(&self.root)?;
-
-        Ok(child)
-    }
-
-    fn load_requirement(&self, hrid: Hrid) -> Result<Requirement, LoadError> {
-        Requirement::load(&self.root, hrid)
-    }
-}
-
 impl Directory<Unloaded> {
     /// Opens a directory at the given path.
     #[must_use]
@@ -80,64 +62,256 @@ impl Directory<Unloaded> {
         }
     }
 
+    /// Create the requirements root, with a fully commented `config.toml`.
+    ///
+    /// The files for a documentation tool are also created if an integration
+    /// is given. Some of these are markdown files which aren't requirements, so
+    /// the configuration should set `allow_unrecognised`.
+    ///
+    /// Returns the paths of the files which were created.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if any of the files already exist, in which case
+    /// nothing is written, or if the files cannot be written.
+    pub fn init(
+        &self,
+        config: &Config,
+        integration: Option<Integration>,
+    ) -> Result<Vec<PathBuf>, InitError> {
+        init::init(&self.root, config, integration)
+    }
+
     /// Load all requirements from disk
     ///
     /// # Errors
     ///
-    /// This method has different behaviour depending on the configuration file
-    /// in the requirements root. If `allow_unrecognised` is `true`, then
-    /// any files with names that are not valid HRIDs, or any files that cannot
-    /// be parsed as requirements, are skipped. if `allow_unrecognised` is
-    /// `false` (the default), then any unrecognised or invalid markdown files
-    /// in the directory will return an error.
+    /// This method fails if the configuration file in the requirements root
+    /// exists but cannot be read or parsed. A missing configuration file is
+    /// not an error; the default configuration is used instead.
+    ///
+    /// Otherwise, this method has different behaviour depending on the
+    /// configuration. Markdown files with names that are not valid
+    /// HRIDs are skipped if `allow_unrecognised` is `true`, and files with
+    /// names that are HRIDs but which cannot be parsed as requirements, or
+    /// whose attributes don't match the configured `attributes`, are skipped
+    /// if `allow_invalid` is `true`. Otherwise (the default), any such
+    /// files cause an error which lists every one of them.
+    ///
+    /// Requirements with the same HRID are always an error, since there is no
+    /// way to tell which of them is intended.
+    ///
+    /// Requirements whose kind is not one of the configured `allowed_kinds`
+    /// are loaded, and a warning is logged for each of their files.
     pub fn load_all(self) -> Result<Directory<Loaded>, DirectoryLoadError> {
-        let config = load_config(&self.root);
+        let config = load_config(&self.root)?;
         let md_paths = collect_markdown_paths(&self.root);
 
-        let (requirements, unrecognised_paths): (Vec<_>, Vec<_>) = md_paths
+        let (requirements, invalid_files): (Vec<_>, Vec<_>) = md_paths
             .par_iter()
-            .map(|path| try_load_requirement(path))
+            .map(|path| {
+                try_load_requirement(path, &config).map(|requirement| (requirement, path.clone()))
+            })
             .partition(Result::is_ok);
 
         let requirements: Vec<_> = requirements.into_iter().map(Result::unwrap).collect();
-        let unrecognised_paths: Vec<_> = unrecognised_paths
+        let mut invalid_files: Vec<_> = invalid_files
             .into_iter()
             .map(Result::unwrap_err)
+            .filter(|file| {
+                let allowed = match file.kind {
+                    InvalidFileKind::Unrecognised => config.allow_unrecognised,
+                    InvalidFileKind::Invalid(_) | InvalidFileKind::Attribute(_) => {
+                        config.allow_invalid
+                    }
+                    InvalidFileKind::DuplicateHrid(_) => false,
+                };
+                if allowed {
+                    tracing::debug!("Skipping {file}");
+                }
+                !allowed
+            })
             .collect();
+        invalid_files.extend(duplicate_hrids(&requirements));
 
-        if !config.allow_unrecognised && !unrecognised_paths.is_empty() {
-            return Err(DirectoryLoadError::UnrecognisedFiles(unrecognised_paths));
+        if !invalid_files.is_empty() {
+            invalid_files.sort_by(|a, b| a.path.cmp(&b.path));
+            return Err(DirectoryLoadError::InvalidFiles(invalid_files));
         }
 
         let mut tree = Tree::with_capacity(requirements.len());
-        for req in requirements {
+        let mut paths = HashMap::with_capacity(requirements.len());
+        for (req, path) in requirements {
+            paths.insert(req.uuid(), path);
             tree.insert(req);
         }
 
+        for (hrid, error) in disallowed_kinds(&tree, &config) {
+            if let Some(path) = tree
+                .find_by_hrid(&hrid)
+                .and_then(|requirement| paths.get(&requirement.uuid()))
+            {
+                tracing::warn!("{}: {error}", path.display());
+            }
+        }
+
         Ok(Directory {
             root: self.root,
-            state: Loaded(tree),
+            state: Loaded {
+                tree,
+                config,
+                paths,
+            },
         })
     }
 }
 
 #[derive(Debug, thiserror::Error)]
 pub enum DirectoryLoadError {
-    UnrecognisedFiles(Vec<PathBuf>),
+    Config { path: PathBuf, error: ConfigError },
+    InvalidFiles(Vec<InvalidFile>),
 }
 
 impl fmt::Display for DirectoryLoadError {
-    fn fmt(&self, _f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        todo!()
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Config { path, error } => write!(f, "{}: {error}", path.display()),
+            Self::InvalidFiles(files) => {
+                write!(f, "failed to load {} file(s):", files.len())?;
+                for file in files {
+                    write!(f, "\n  {file}")?;
+                }
+
+                let has = |unrecognised: bool| {
+                    files.iter().any(|file| match file.kind {
+                        InvalidFileKind::Unrecognised => unrecognised,
+                        InvalidFileKind::Invalid(_) | InvalidFileKind::Attribute(_) => {
+                            !unrecognised
+                        }
+                        InvalidFileKind::DuplicateHrid(_) => false,
+                    })
+                };
+                if has(true) {
+                    write!(
+                        f,
+                        "\nset 'allow_unrecognised = true' in config.toml to skip files whose \
+                         names are not HRIDs"
+                    )?;
+                }
+                if has(false) {
+                    write!(
+                        f,
+                        "\nset 'allow_invalid = true' in config.toml to skip requirements which \
+                         are not valid"
+                    )?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A markdown file in the requirements directory which could not be loaded as
+/// a requirement.
+#[derive(Debug, thiserror::Error)]
+#[error("{}: {kind}", path.display())]
+pub struct InvalidFile {
+    /// The path of the file.
+    pub path: PathBuf,
+
+    /// Why the file could not be loaded.
+    pub kind: InvalidFileKind,
+}
+
+/// The reason a markdown file could not be loaded as a requirement.
+#[derive(Debug, thiserror::Error)]
+pub enum InvalidFileKind {
+    /// The name of the file is not an HRID.
+    #[error("file name is not an HRID")]
+    Unrecognised,
+
+    /// The name of the file is an HRID, but the file is not a valid
+    /// requirement.
+    #[error(transparent)]
+    Invalid(LoadError),
+
+    /// The file is a valid requirement, but its attributes don't match the
+    /// configured schema.
+    #[error(transparent)]
+    Attribute(SchemaError),
+
+    /// The file is a valid requirement, but another file has the same HRID.
+    ///
+    /// HRIDs which differ only in their zero padding, such as `SYS-001` and
+    /// `SYS-1`, are the same.
+    #[error("the HRID is also used by {}", .0.display())]
+    DuplicateHrid(PathBuf),
+}
+
+/// Finds the requirements which share an HRID with another requirement.
+///
+/// Each file is reported along with one of the other files using its HRID.
+fn duplicate_hrids(requirements: &[(Requirement, PathBuf)]) -> Vec<InvalidFile> {
+    let mut by_hrid: HashMap<&Hrid, Vec<&PathBuf>> = HashMap::new();
+    for (requirement, path) in requirements {
+        by_hrid.entry(requirement.hrid()).or_default().push(path);
+    }
+
+    let mut duplicates = Vec::new();
+    for mut paths in by_hrid.into_values().filter(|paths| paths.len() > 1) {
+        paths.sort();
+        for (i, path) in paths.iter().enumerate() {
+            let other = if i == 0 { paths[1] } else { paths[0] };
+            duplicates.push(InvalidFile {
+                path: (*path).clone(),
+                kind: InvalidFileKind::DuplicateHrid(other.clone()),
+            });
+        }
     }
+    duplicates
+}
+
+fn disallowed_kinds(tree: &Tree, config: &Config) -> Vec<(Hrid, DisallowedKindError)> {
+    let mut disallowed: Vec<_> = tree
+        .requirements()
+        .filter_map(|requirement| {
+            let hrid = requirement.hrid();
+            config
+                .check_kind(hrid.kind())
+                .err()
+                .map(|error| (hrid.clone(), error))
+        })
+        .collect();
+    disallowed.sort_by(|(a, _), (b, _)| a.cmp(b));
+    disallowed
+}
+
+/// Renders a configuration file with the number of digits changed, keeping the
+/// rest of the file (including any comments) intact.
+///
+/// If the file doesn't exist, the whole configuration is rendered.
+fn config_with_digits(path: &Path, config: &Config) -> Result<String, MigrateDigitsError> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(config.to_commented_toml()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut document: toml_edit::DocumentMut = contents.parse()?;
+    document["digits"] = toml_edit::value(i64::try_from(config.digits()).unwrap_or(i64::MAX));
+    Ok(document.to_string())
 }
 
-fn load_config(root: &Path) -> Config {
+fn load_config(root: &Path) -> Result<Config, DirectoryLoadError> {
     let path = root.join("config.toml");
-    Config::load(&path).unwrap_or_else(|e| {
-        tracing::debug!("Failed to load config: {e}");
-        Config::default()
-    })
+    match Config::load(&path) {
+        Ok(config) => Ok(config),
+        Err(ConfigError::Io(e)) if e.kind() == io::ErrorKind::NotFound => {
+            tracing::debug!("No config file, using the default configuration");
+            Ok(Config::default())
+        }
+        Err(error) => Err(DirectoryLoadError::Config { path, error }),
+    }
 }
 
 fn collect_markdown_paths(root: &PathBuf) -> Vec<PathBuf> {
@@ -149,33 +323,91 @@ fn collect_markdown_paths(root: &PathBuf) -> Vec<PathBuf> {
         .collect()
 }
 
-fn try_load_requirement(path: &Path) -> Result<Requirement, PathBuf> {
-    let Some(stem) = path.file_stem().map(|s| s.to_string_lossy().to_string()) else {
-        tracing::debug!("Skipping file without valid stem: {}", path.display());
-        return Err(path.to_path_buf());
+fn try_load_requirement(path: &Path, config: &Config) -> Result<Requirement, InvalidFile> {
+    let invalid = |kind| InvalidFile {
+        path: path.to_path_buf(),
+        kind,
     };
 
-    let Ok(hrid) = Hrid::from_str(&stem) else {
-        tracing::debug!("Skipping file with invalid HRID: {}", stem);
-        return Err(path.to_path_buf());
+    let Some(hrid) = path
+        .file_stem()
+        .and_then(OsStr::to_str)
+        .and_then(|stem| Hrid::from_str(stem).ok())
+    else {
+        return Err(invalid(InvalidFileKind::Unrecognised));
     };
 
     let dir = path.parent().unwrap_or(path).to_path_buf();
 
-    match Requirement::load(&dir, hrid) {
-        Ok(req) => Ok(req),
-        Err(e) => {
-            tracing::debug!(
-                "Failed to load requirement from {}: {:?}",
-                path.display(),
-                e
-            );
-            Err(path.to_path_buf())
-        }
-    }
+    let mut requirement =
+        Requirement::load(&dir, hrid).map_err(|e| invalid(InvalidFileKind::Invalid(e)))?;
+    requirement
+        .apply_schema(config.attributes())
+        .map_err(|e| invalid(InvalidFileKind::Attribute(e)))?;
+    Ok(requirement)
 }
 
 impl Directory<Loaded> {
+    /// The in-memory tree of requirements loaded from the directory.
+    #[must_use]
+    pub const fn tree(&self) -> &Tree {
+        &self.state.tree
+    }
+
+    /// The root of the directory requirements are stored in.
+    #[must_use]
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// The path of the file a requirement is stored in.
+    ///
+    /// This is the file the requirement was loaded from, which may be in a
+    /// subdirectory of the root. A requirement which is not in the directory
+    /// would be stored in the root.
+    #[must_use]
+    pub fn path(&self, hrid: &Hrid) -> PathBuf {
+        self.state.tree.find_by_hrid(hrid).map_or_else(
+            || self.root.join(hrid.to_string()).with_extension("md"),
+            |requirement| self.file(requirement),
+        )
+    }
+
+    /// The directory a requirement is stored in.
+    ///
+    /// This is the directory its file was loaded from, or the root for a new
+    /// requirement.
+    fn dir(&self, uuid: Uuid) -> &Path {
+        self.state
+            .paths
+            .get(&uuid)
+            .and_then(|path| path.parent())
+            .unwrap_or(&self.root)
+    }
+
+    /// The file a requirement is stored in, named after its current HRID.
+    fn file(&self, requirement: &Requirement) -> PathBuf {
+        self.dir(requirement.uuid())
+            .join(requirement.hrid().to_string())
+            .with_extension("md")
+    }
+
+    /// Save a requirement to the file it is stored in.
+    fn save(&self, uuid: Uuid) -> io::Result<()> {
+        self.state
+            .tree
+            .requirement(uuid)
+            .map_or(Ok(()), |requirement| requirement.save(self.dir(uuid)))
+    }
+
+    /// The configuration loaded from the directory's `config.toml`.
+    ///
+    /// If the file is missing, this is the default configuration.
+    #[must_use]
+    pub const fn config(&self) -> &Config {
+        &self.state.config
+    }
+
     /// Add a new requirement to the directory.
     ///
     /// # Errors
@@ -183,15 +415,51 @@ impl Directory<Loaded> {
     /// This method can fail if:
     ///
     /// - the provided `kind` is an empty string
+    /// - the provided `kind` is not one of the configured `allowed_kinds`
+    /// - the configured `attributes` include a required attribute without a
+    ///   default
     /// - the requirement file cannot be written to
     pub fn add_requirement(&mut self, kind: String) -> Result<Requirement, AddRequirementError> {
-        let tree = &mut self.state.0;
+        self.add_requirement_with_attributes(kind, BTreeMap::new())
+    }
+
+    /// Add a new requirement to the directory, with the given custom
+    /// attributes.
+    ///
+    /// # Errors
+    ///
+    /// This method can fail if:
+    ///
+    /// - the provided `kind` is an empty string
+    /// - the provided `kind` is not one of the configured `allowed_kinds`
+    /// - the attributes don't match the configured `attributes`, or don't
+    ///   include a required attribute which has no default
+    /// - the requirement file cannot be written to
+    pub fn add_requirement_with_attributes(
+        &mut self,
+        kind: String,
+        attributes: BTreeMap<String, AttributeValue>,
+    ) -> Result<Requirement, AddRequirementError> {
+        self.state.config.check_kind(&kind)?;
+        let tree = &mut self.state.tree;
 
         let id = tree.next_index(&kind);
+        let hrid = Hrid::new(kind, id)?.with_digits(self.state.config.digits());
 
-        let requirement = Requirement::new(Hrid::new(kind, id)?, String::new());
+        let mut requirement = Requirement::new(hrid, String::new());
+        for (name, value) in attributes {
+            requirement.set_attribute(name, value);
+        }
+        requirement.apply_schema(self.state.config.attributes())?;
+        requirement.check_required(self.state.config.attributes())?;
 
         requirement.save(&self.root)?;
+        self.state.paths.insert(
+            requirement.uuid(),
+            self.root
+                .join(requirement.hrid().to_string())
+                .with_extension("md"),
+        );
         tree.insert(requirement.clone());
 
         tracing::info!("Added requirement: {}", requirement.hrid());
@@ -199,124 +467,1415 @@ impl Directory<Loaded> {
         Ok(requirement)
     }
 
-    /// Update the human-readable IDs (HRIDs) of all 'parents' references in the
-    /// requirements.
-    ///
-    /// These can become out of sync if requirement files are renamed.
+    /// Link two requirements together with a parent-child relationship.
     ///
     /// # Errors
     ///
-    /// This method returns an error if some of the requirements cannot be saved
-    /// to disk. This method does *not* fail fast. That is, it will attempt
-    /// to save all the requirements before returning the error.
-    pub fn update_hrids(&mut self) -> Result<(), UpdateHridsError> {
-        let tree = &mut self.state.0;
-        let updated: Vec<_> = tree.update_hrids().collect();
+    /// This method can fail if:
+    ///
+    /// - either the child or parent requirement cannot be found
+    /// - the configured `parent_kinds` don't allow the child to have a parent
+    ///   of that kind
+    /// - the link would create a cycle
+    /// - the child requirement file cannot be written to
+    pub fn link_requirement(
+        &mut self,
+        child: Hrid,
+        parent: Hrid,
+    ) -> Result<Requirement, LinkRequirementError> {
+        self.link_requirement_as(child, parent, None)
+    }
 
-        let failures = updated
-            .iter()
-            .filter_map(|&id| {
-                let requirement = tree.requirement(id)?;
-                requirement.save(&self.root).err().map(|e| {
-                    (
-                        // TODO: manually constructing the path here is brittle. This logic should
-                        // be centralised.
-                        self.root
-                            .join(requirement.hrid().to_string())
-                            .with_extension("md"),
-                        e,
-                    )
-                })
-            })
-            .collect();
+    /// Link two requirements together with a parent-child relationship of the
+    /// given type, such as 'verifies'.
+    ///
+    /// Linking requirements which are already linked replaces the type of the
+    /// existing link if `relation` is given, and keeps it otherwise.
+    ///
+    /// # Errors
+    ///
+    /// This method can fail if:
+    ///
+    /// - the relation is not declared in the configured `relations`
+    /// - either the child or parent requirement cannot be found
+    /// - the configured `parent_kinds` don't allow the child to have a parent
+    ///   of that kind
+    /// - the link would create a cycle
+    /// - the child requirement file cannot be written to
+    pub fn link_requirement_as(
+        &mut self,
+        child: Hrid,
+        parent: Hrid,
+        relation: Option<String>,
+    ) -> Result<Requirement, LinkRequirementError> {
+        if let Some(relation) = &relation {
+            self.state.config.check_relation(relation)?;
+        }
+        let tree = &mut self.state.tree;
+
+        let child = tree
+            .find_by_hrid(&child)
+            .ok_or(LinkRequirementError::NotFound(child))?;
+        let parent = tree
+            .find_by_hrid(&parent)
+            .ok_or(LinkRequirementError::NotFound(parent))?;
+        self.state
+            .config
+            .check_parent_kind(child.hrid().kind(), parent.hrid().kind())?;
+        let (child, parent) = (child.uuid(), parent.uuid());
+
+        let child = tree.link_as(child, parent, relation)?.clone();
+        self.save(child.uuid())?;
 
-        NonEmpty::from_vec(failures).map_or(Ok(()), |failures| Err(UpdateHridsError { failures }))
+        Ok(child)
     }
-}
 
-#[derive(Debug, thiserror::Error)]
-#[error("failed to add requirement: {0}")]
-pub enum AddRequirementError {
-    Kind(#[from] EmptyStringError),
-    Io(#[from] io::Error),
-}
+    /// Remove the link between a child requirement and one of its parents.
+    ///
+    /// The parent may be given by its current HRID, by the HRID recorded in
+    /// the child's link, or by its UUID. This means links to parents which have
+    /// since been deleted can still be removed.
+    ///
+    /// # Errors
+    ///
+    /// This method can fail if:
+    ///
+    /// - the child requirement cannot be found
+    /// - the child has no matching parent
+    /// - the child requirement file cannot be written to
+    pub fn unlink_requirement(
+        &mut self,
+        child: &Hrid,
+        parent: &str,
+    ) -> Result<Requirement, UnlinkRequirementError> {
+        let tree = &mut self.state.tree;
+
+        let requirement = tree
+            .find_by_hrid(child)
+            .ok_or_else(|| UnlinkRequirementError::NotFound(child.clone()))?;
+        let child_id = requirement.uuid();
+
+        let parent_id = find_parent(tree, requirement, parent);
+
+        let not_linked = || UnlinkRequirementError::NotLinked {
+            child: child.clone(),
+            parent: parent.to_string(),
+        };
+        let parent_id = parent_id.ok_or_else(not_linked)?;
+
+        tree.unlink(child_id, parent_id).ok_or_else(not_linked)?;
+        let requirement = tree.requirement(child_id).ok_or_else(not_linked)?.clone();
+        self.save(child_id)?;
 
-#[derive(Debug, thiserror::Error)]
-pub struct UpdateHridsError {
-    failures: NonEmpty<(PathBuf, io::Error)>,
-}
+        Ok(requirement)
+    }
 
-impl fmt::Display for UpdateHridsError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        const MAX_DISPLAY: usize = 5;
+    /// Change the HRID of a requirement, and rewrite every reference to it.
+    ///
+    /// The requirement file is moved, and its UUID is preserved. The parent
+    /// HRIDs recorded in its children are updated, and whole-word references
+    /// to the old HRID in the body of any requirement are rewritten. The kind
+    /// and namespace may change.
+    ///
+    /// Returns the HRIDs of the other requirements which were modified.
+    ///
+    /// # Errors
+    ///
+    /// This method can fail if:
+    ///
+    /// - the requirement cannot be found
+    /// - the new kind is not one of the configured `allowed_kinds`
+    /// - the configured `parent_kinds` don't allow a requirement of the new
+    ///   kind to keep its parents or children
+    /// - a requirement (or any other file) already exists with the new HRID
+    /// - a requirement file cannot be written to, or the old file cannot be
+    ///   removed
+    pub fn rename_requirement(
+        &mut self,
+        old: &Hrid,
+        new: Hrid,
+    ) -> Result<Vec<Hrid>, RenameRequirementError> {
+        let requirement = self
+            .state
+            .tree
+            .find_by_hrid(old)
+            .ok_or_else(|| RenameRequirementError::NotFound(old.clone()))?;
+        self.state.config.check_kind(new.kind())?;
+        let uuid = requirement.uuid();
+        let old_path = self.file(requirement);
+
+        if new.kind() != old.kind() {
+            let tree = &self.state.tree;
+            for parent in tree.parents(uuid).filter_map(|id| tree.requirement(id)) {
+                self.state
+                    .config
+                    .check_parent_kind(new.kind(), parent.hrid().kind())?;
+            }
+            for child in tree.children(uuid).filter_map(|id| tree.requirement(id)) {
+                self.state
+                    .config
+                    .check_parent_kind(child.hrid().kind(), new.kind())?;
+            }
+        }
 
-        write!(f, "failed to update HRIDS: ")?;
+        let new = new.with_digits(self.state.config.digits());
+        let new_path = self.dir(uuid).join(new.to_string()).with_extension("md");
+        let tree = &mut self.state.tree;
 
-        let total = self.failures.len();
+        if new != *old && new_path.exists() {
+            return Err(RenameError::Taken(new).into());
+        }
 
-        let displayed_paths: Vec<String> = self
-            .failures
-            .iter()
-            .take(MAX_DISPLAY)
-            .map(|(p, _e)| p.display().to_string())
-            .collect();
+        let modified = tree.rename(uuid, new)?;
 
-        let msg = displayed_paths.join(", ");
+        let mut others = Vec::new();
+        for id in modified {
+            self.save(id)?;
+            if let Some(requirement) = self.state.tree.requirement(id).filter(|_| id != uuid) {
+                others.push(requirement.hrid().clone());
+            }
+        }
 
-        if total <= MAX_DISPLAY {
-            write!(f, "{msg}")
-        } else {
-            write!(f, "{msg}... (and {} more)", total - MAX_DISPLAY)
+        if old_path != new_path {
+            std::fs::remove_file(old_path)?;
         }
+        self.state.paths.insert(uuid, new_path);
+
+        others.sort();
+        Ok(others)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use tempfile::TempDir;
+    /// Delete a requirement, handling its children according to `policy`.
+    ///
+    /// The requirement file is removed, and then any modified children are
+    /// saved.
+    ///
+    /// Returns the HRIDs of the children which were modified.
+    ///
+    /// # Errors
+    ///
+    /// This method can fail if:
+    ///
+    /// - the requirement cannot be found
+    /// - the requirement has children, and the policy is
+    ///   [`DeletePolicy::Refuse`]
+    /// - the policy is [`DeletePolicy::Reparent`], and the configured
+    ///   `parent_kinds` don't allow a child to link to one of the requirement's
+    ///   parents
+    /// - the requirement file cannot be removed, in which case no children are
+    ///   modified
+    /// - a child requirement file cannot be written to
+    pub fn delete_requirement(
+        &mut self,
+        hrid: &Hrid,
+        policy: DeletePolicy,
+    ) -> Result<Vec<Hrid>, DeleteRequirementError> {
+        let requirement = self
+            .state
+            .tree
+            .find_by_hrid(hrid)
+            .ok_or_else(|| DeleteRequirementError::NotFound(hrid.clone()))?;
+        let uuid = requirement.uuid();
+        let path = self.file(requirement);
+
+        // Remove the file before touching any children, so that nothing is
+        // changed if it can't be removed
+        let tree = &self.state.tree;
+        tree.check_delete(uuid, policy)?;
+        if policy == DeletePolicy::Reparent {
+            for child in tree.children(uuid).filter_map(|id| tree.requirement(id)) {
+                for parent in tree.parents(uuid).filter_map(|id| tree.requirement(id)) {
+                    self.state
+                        .config
+                        .check_parent_kind(child.hrid().kind(), parent.hrid().kind())?;
+                }
+            }
+        }
+        std::fs::remove_file(path)?;
+        self.state.paths.remove(&uuid);
 
-    use super::*;
-    use crate::Requirement;
+        let (_, modified) = self.state.tree.delete(uuid, policy)?;
 
-    fn setup_temp_directory() -> (TempDir, Directory<Loaded>) {
-        let tmp = TempDir::new().expect("failed to create temp dir");
-        let path = tmp.path().to_path_buf();
-        (tmp, Directory::new(path).load_all().unwrap())
+        let mut children = Vec::new();
+        for id in modified {
+            self.save(id)?;
+            if let Some(requirement) = self.state.tree.requirement(id) {
+                children.push(requirement.hrid().clone());
+            }
+        }
+
+        children.sort();
+        Ok(children)
     }
 
-    #[test]
-    fn can_add_requirement() {
-        let (_tmp, mut dir) = setup_temp_directory();
-        let r1 = dir.add_requirement("REQ".to_string()).unwrap();
+    /// Change the number of digits HRIDs are padded to.
+    ///
+    /// Every requirement file is renamed, and every reference to an HRID is
+    /// updated, in the same way as [`Directory::rename_requirement`]. The new
+    /// number of digits is written to `config.toml`, which is created if it
+    /// doesn't exist.
+    ///
+    /// The renamed files and the configuration file are written before any
+    /// existing file is changed. If one of them can't be written, the new files
+    /// are removed and nothing is changed.
+    ///
+    /// Returns the new HRIDs of the requirements which were renamed.
+    ///
+    /// # Errors
+    ///
+    /// This method can fail if:
+    ///
+    /// - the configuration file cannot be read or parsed
+    /// - a file already exists with the new name of a requirement
+    /// - the requirement files or the configuration file cannot be written to,
+    ///   or an old requirement file cannot be removed
+    pub fn migrate_digits(&mut self, digits: usize) -> Result<Vec<Hrid>, MigrateDigitsError> {
+        let config_path = self.root.join("config.toml");
+        let config = self.state.config.clone().with_digits(digits);
+        let contents = config_with_digits(&config_path, &config)?;
+
+        let mut tree = self.state.tree.clone();
+        let modified = tree.set_digits(digits)?;
+
+        let mut renamed = Vec::new();
+        let mut rewritten = Vec::new();
+        for uuid in modified {
+            let (Some(before), Some(after)) =
+                (self.state.tree.requirement(uuid), tree.requirement(uuid))
+            else {
+                continue;
+            };
+            let old_path = self.file(before);
+            let new_path = self
+                .dir(uuid)
+                .join(after.hrid().to_string())
+                .with_extension("md");
+            if old_path == new_path {
+                rewritten.push(uuid);
+            } else if new_path.exists() {
+                return Err(RenameError::Taken(after.hrid().clone()).into());
+            } else {
+                renamed.push((uuid, old_path, new_path));
+            }
+        }
 
-        assert_eq!(r1.hrid().to_string(), "REQ-001");
+        let written = renamed
+            .iter()
+            .filter_map(|(uuid, _, new_path)| Some((tree.requirement(*uuid)?, new_path)))
+            .try_for_each(|(requirement, new_path)| {
+                requirement.save(new_path.parent().unwrap_or(&self.root))
+            })
+            .and_then(|()| std::fs::write(&config_path, contents));
+        if let Err(e) = written {
+            for (_, _, new_path) in &renamed {
+                // Some of the files may not have been written
+                let _ = std::fs::remove_file(new_path);
+            }
+            return Err(e.into());
+        }
 
-        let loaded =
-            Requirement::load(&dir.root, r1.hrid().clone()).expect("should load saved requirement");
-        assert_eq!(loaded.uuid(), r1.uuid());
+        // The configuration now matches the new files, so the old ones can go
+        self.state.tree = tree;
+        self.state.config = config;
+        let mut hrids = Vec::new();
+        for (uuid, old_path, new_path) in renamed {
+            std::fs::remove_file(old_path)?;
+            self.state.paths.insert(uuid, new_path);
+            hrids.extend(self.state.tree.requirement(uuid).map(|r| r.hrid().clone()));
+        }
+        for uuid in rewritten {
+            self.save(uuid)?;
+        }
+
+        hrids.sort();
+        Ok(hrids)
     }
 
-    #[test]
-    fn can_add_multiple_requirements_with_incrementing_id() {
-        let (_tmp, mut dir) = setup_temp_directory();
-        let r1 = dir.add_requirement("REQ".to_string()).unwrap();
-        let r2 = dir.add_requirement("REQ".to_string()).unwrap();
+    /// Find the cycles in the graph of parent links.
+    ///
+    /// Requirements must form a directed acyclic graph, so any cycles found
+    /// here indicate an invalid set of requirements.
+    #[must_use]
+    pub fn find_cycles(&self) -> Vec<Cycle> {
+        self.state.tree.find_cycles()
+    }
 
-        assert_eq!(r1.hrid().to_string(), "REQ-001");
-        assert_eq!(r2.hrid().to_string(), "REQ-002");
+    /// Find the parent links which are 'suspect'.
+    ///
+    /// A link is suspect if the parent's content or tags have changed since the
+    /// link was created or last reviewed.
+    #[must_use]
+    pub fn suspect_links(&self) -> Vec<SuspectLink> {
+        self.state.tree.suspect_links()
     }
 
-    #[test]
-    fn can_link_two_requirements() {
-        let (_tmp, mut dir) = setup_temp_directory();
-        let parent = dir.add_requirement("SYS".to_string()).unwrap();
-        let child = dir.add_requirement("USR".to_string()).unwrap();
+    /// Find the requirements which have changed since a git revision.
+    ///
+    /// A requirement has changed if its fingerprint differs from its
+    /// fingerprint at that revision, or if it didn't exist then. This includes
+    /// uncommitted changes, and requirement files which are not yet tracked by
+    /// git (unless git ignores them). A requirement which doesn't match the
+    /// current attribute schema at that revision is considered changed.
+    ///
+    /// Returns the HRIDs of the changed requirements, in order.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if git cannot be run, the root is not in a git
+    /// repository, or the revision does not exist.
+    pub fn changed_since(&self, revision: &str) -> Result<Vec<Hrid>, GitError> {
+        let requirements: HashMap<&Path, Uuid> = self
+            .state
+            .paths
+            .iter()
+            .map(|(&uuid, path)| (path.as_path(), uuid))
+            .collect();
 
-        Directory::new(dir.root.clone())
-            .link_requirement(child.hrid().clone(), parent.hrid().clone())
-            .unwrap();
+        let mut changed = Vec::new();
+        for path in git::changed_files(&self.root, revision)? {
+            let Some(requirement) = requirements
+                .get(self.root.join(&path).as_path())
+                .and_then(|&uuid| self.state.tree.requirement(uuid))
+            else {
+                continue;
+            };
+
+            let previous = git::show(&self.root, revision, &path)?;
+            let unchanged = previous.is_some_and(|previous| {
+                Requirement::read(&mut previous.as_slice(), requirement.hrid().clone()).is_ok_and(
+                    |mut previous| {
+                        previous
+                            .apply_schema(self.state.config.attributes())
+                            .is_ok()
+                            && previous.fingerprint() == requirement.fingerprint()
+                    },
+                )
+            });
+
+            if !unchanged {
+                changed.push(requirement.hrid().clone());
+            }
+        }
 
-        let updated =
+        changed.sort();
+        changed.dedup();
+        Ok(changed)
+    }
+
+    /// Mark a requirement's parent links as reviewed.
+    ///
+    /// This replaces the stored parent fingerprints with the parents' current
+    /// fingerprints, clearing any suspect links. If `parent` is given, only the
+    /// link to that parent is reviewed.
+    ///
+    /// Returns the HRIDs of the parents whose links were suspect.
+    ///
+    /// # Errors
+    ///
+    /// This method can fail if:
+    ///
+    /// - either the child or parent requirement cannot be found
+    /// - the parent is not a parent of the child
+    /// - the child requirement file cannot be written to
+    pub fn review(
+        &mut self,
+        child: &Hrid,
+        parent: Option<&Hrid>,
+    ) -> Result<Vec<Hrid>, ReviewError> {
+        let tree = &mut self.state.tree;
+
+        let child_requirement = tree
+            .find_by_hrid(child)
+            .ok_or_else(|| ReviewError::NotFound(child.clone()))?;
+        let child_id = child_requirement.uuid();
+
+        let parent_id = match parent {
+            Some(parent) => {
+                let parent_id = tree
+                    .find_by_hrid(parent)
+                    .ok_or_else(|| ReviewError::NotFound(parent.clone()))?
+                    .uuid();
+                if !child_requirement.parents().any(|(id, _)| id == parent_id) {
+                    return Err(ReviewError::NotLinked {
+                        child: child.clone(),
+                        parent: parent.clone(),
+                    });
+                }
+                Some(parent_id)
+            }
+            None => None,
+        };
+
+        let reviewed = tree.review(child_id, parent_id);
+        if !reviewed.is_empty() {
+            self.save(child_id)?;
+        }
+
+        Ok(reviewed
+            .into_iter()
+            .filter_map(|id| self.state.tree.requirement(id))
+            .map(|parent| parent.hrid().clone())
+            .collect())
+    }
+
+    /// Mark every suspect link as reviewed.
+    ///
+    /// Only links from children of the given `kind` and `namespace` are
+    /// reviewed. An empty namespace matches every namespace.
+    ///
+    /// Returns the links that were reviewed.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if a child requirement file cannot be written to.
+    pub fn review_all(
+        &mut self,
+        kind: Option<&str>,
+        namespace: &[String],
+    ) -> Result<Vec<SuspectLink>, ReviewError> {
+        let tree = &mut self.state.tree;
+
+        let suspect_links: Vec<_> = tree
+            .suspect_links()
+            .into_iter()
+            .filter(|link| kind.is_none_or(|kind| link.child_hrid.kind() == kind))
+            .filter(|link| link.child_hrid.namespace_starts_with(namespace))
+            .collect();
+
+        let mut children: Vec<_> = suspect_links.iter().map(|link| link.child).collect();
+        children.dedup();
+
+        for child in children {
+            self.state.tree.review(child, None);
+            self.save(child)?;
+        }
+
+        Ok(suspect_links)
+    }
+
+    /// Replace a requirement with an edited version of itself, and save it.
+    ///
+    /// The requirement with the same UUID is replaced. Returns the HRIDs of the
+    /// children whose links have become suspect as a result of the edit.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if [`Directory::check_update`] does, if the
+    /// attributes don't match the configured `attributes`, or if the
+    /// requirement file cannot be written to.
+    pub fn update_requirement(
+        &mut self,
+        mut requirement: Requirement,
+    ) -> Result<Vec<Hrid>, UpdateRequirementError> {
+        requirement.apply_schema(self.state.config.attributes())?;
+        self.check_update(&requirement)?;
+        let tree = &mut self.state.tree;
+        let uuid = requirement.uuid();
+
+        let suspect = tree.replace(requirement)?;
+        self.save(uuid)?;
+
+        let mut suspect: Vec<Hrid> = suspect
+            .into_iter()
+            .filter_map(|child| self.state.tree.requirement(child))
+            .map(|child| child.hrid().clone())
+            .collect();
+        suspect.sort();
+        Ok(suspect)
+    }
+
+    /// Check that a requirement could replace the one with the same UUID,
+    /// without replacing it.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if [`Tree::check_replace`] does, if a required
+    /// attribute isn't set, or if the configured `parent_kinds` don't allow one
+    /// of the requirement's new parents.
+    pub fn check_update(&self, requirement: &Requirement) -> Result<(), UpdateRequirementError> {
+        let tree = &self.state.tree;
+        tree.check_replace(requirement)?;
+        requirement.check_required(self.state.config.attributes())?;
+        let uuid = requirement.uuid();
+        let existing = tree.requirement(uuid).ok_or(ReplaceError::NotFound(uuid))?;
+
+        let new_parents = requirement
+            .parents()
+            .filter(|&(parent, _)| existing.parents().all(|(id, _)| id != parent))
+            .filter_map(|(parent, _)| tree.requirement(parent));
+        for parent in new_parents {
+            self.state
+                .config
+                .check_parent_kind(requirement.hrid().kind(), parent.hrid().kind())?;
+        }
+
+        Ok(())
+    }
+
+    /// Replace the tags on a requirement.
+    ///
+    /// Tags contribute to the fingerprint, so changing them makes the links
+    /// from the requirement's children suspect. Returns the HRIDs of the
+    /// children whose links have become suspect.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the requirement cannot be found, or if its file
+    /// cannot be written to.
+    pub fn set_tags(
+        &mut self,
+        hrid: &Hrid,
+        tags: BTreeSet<String>,
+    ) -> Result<Vec<Hrid>, SetTagsError> {
+        let tree = &mut self.state.tree;
+
+        let requirement = tree
+            .find_by_hrid(hrid)
+            .ok_or_else(|| SetTagsError::NotFound(hrid.clone()))?;
+        let uuid = requirement.uuid();
+
+        if *requirement.tags() == tags {
+            return Ok(Vec::new());
+        }
+
+        let suspect = tree.set_tags(uuid, tags).unwrap_or_default();
+        self.save(uuid)?;
+
+        let mut suspect: Vec<Hrid> = suspect
+            .into_iter()
+            .filter_map(|child| self.state.tree.requirement(child))
+            .map(|child| child.hrid().clone())
+            .collect();
+        suspect.sort();
+        Ok(suspect)
+    }
+
+    /// Finds requirements whose kind is not one of the configured
+    /// `allowed_kinds`, sorted by HRID.
+    #[must_use]
+    pub fn disallowed_kinds(&self) -> Vec<(Hrid, DisallowedKindError)> {
+        disallowed_kinds(&self.state.tree, &self.state.config)
+    }
+
+    /// Finds required attributes which aren't set, sorted by HRID.
+    ///
+    /// These don't prevent the directory from loading, so that the
+    /// requirements can still be checked and edited.
+    #[must_use]
+    pub fn missing_attributes(&self) -> Vec<(Hrid, AttributeError)> {
+        let mut missing: Vec<_> = self
+            .state
+            .tree
+            .requirements()
+            .filter_map(|requirement| {
+                let errors = requirement
+                    .check_required(self.state.config.attributes())
+                    .err()?;
+                Some((requirement.hrid(), errors))
+            })
+            .flat_map(|(hrid, errors)| {
+                errors
+                    .errors()
+                    .to_vec()
+                    .into_iter()
+                    .map(move |error| (hrid.clone(), error))
+            })
+            .collect();
+        missing.sort_by(|(a, _), (b, _)| a.cmp(b));
+        missing
+    }
+
+    /// Finds links to parents of a kind which the configured `parent_kinds`
+    /// don't allow, as pairs of child and parent HRIDs sorted by child.
+    #[must_use]
+    pub fn disallowed_parents(&self) -> Vec<(Hrid, Hrid, DisallowedParentError)> {
+        let tree = &self.state.tree;
+        let mut disallowed: Vec<_> = tree
+            .requirements()
+            .flat_map(|child| {
+                tree.parents(child.uuid())
+                    .filter_map(|parent| tree.requirement(parent))
+                    .filter_map(move |parent| {
+                        let (child, parent) = (child.hrid(), parent.hrid());
+                        self.state
+                            .config
+                            .check_parent_kind(child.kind(), parent.kind())
+                            .err()
+                            .map(|error| (child.clone(), parent.clone(), error))
+                    })
+            })
+            .collect();
+        disallowed.sort_by(|(a, b, _), (c, d, _)| (a, b).cmp(&(c, d)));
+        disallowed
+    }
+
+    /// Finds links whose relation is not declared in the configured
+    /// `relations`, as pairs of child and parent HRIDs sorted by child.
+    #[must_use]
+    pub fn unknown_relations(&self) -> Vec<(Hrid, Hrid, UnknownRelationError)> {
+        let mut unknown: Vec<_> = self
+            .state
+            .tree
+            .requirements()
+            .flat_map(|child| {
+                child.parents().filter_map(|(_, parent)| {
+                    let relation = parent.relation.as_deref()?;
+                    self.state
+                        .config
+                        .check_relation(relation)
+                        .err()
+                        .map(|error| (child.hrid().clone(), parent.hrid.clone(), error))
+                })
+            })
+            .collect();
+        unknown.sort_by(|(a, b, _), (c, d, _)| (a, b).cmp(&(c, d)));
+        unknown
+    }
+
+    /// Finds parent links which cannot be followed, along with the file of
+    /// the child requirement.
+    ///
+    /// See [`Tree::broken_links`].
+    #[must_use]
+    pub fn broken_links(&self) -> Vec<(PathBuf, BrokenLink)> {
+        self.state
+            .tree
+            .broken_links()
+            .into_iter()
+            .map(|link| (self.path(&link.child_hrid), link))
+            .collect()
+    }
+
+    /// Removes every broken link, saving the affected requirements.
+    ///
+    /// Returns the links which were removed, along with the file of the child
+    /// requirement.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an affected requirement cannot be saved.
+    pub fn prune_broken_links(&mut self) -> io::Result<Vec<(PathBuf, BrokenLink)>> {
+        let broken_links = self.state.tree.prune_broken_links();
+
+        let mut saved = HashSet::new();
+        for link in &broken_links {
+            if saved.insert(link.child) {
+                self.save(link.child)?;
+            }
+        }
+
+        Ok(broken_links
+            .into_iter()
+            .map(|link| (self.path(&link.child_hrid), link))
+            .collect())
+    }
+
+    /// Update the human-readable IDs (HRIDs) of all 'parents' references in the
+    /// requirements.
+    ///
+    /// These can become out of sync if requirement files are renamed.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if some of the requirements cannot be saved
+    /// to disk. This method does *not* fail fast. That is, it will attempt
+    /// to save all the requirements before returning the error.
+    pub fn update_hrids(&mut self) -> Result<(), UpdateHridsError> {
+        let updated: Vec<_> = self.state.tree.update_hrids().collect();
+        let tree = &self.state.tree;
+
+        let failures = updated
+            .iter()
+            .filter_map(|&id| {
+                let requirement = tree.requirement(id)?;
+                self.save(id).err().map(|e| (self.file(requirement), e))
+            })
+            .collect();
+
+        NonEmpty::from_vec(failures).map_or(Ok(()), |failures| Err(UpdateHridsError { failures }))
+    }
+}
+
+/// Finds the parent of a requirement by UUID, current HRID, or stored HRID.
+fn find_parent(tree: &Tree, requirement: &Requirement, parent: &str) -> Option<Uuid> {
+    let mut parents = requirement.parents();
+
+    if let Ok(uuid) = Uuid::parse_str(parent) {
+        return parents
+            .map(|(parent_id, _)| parent_id)
+            .find(|&parent_id| parent_id == uuid);
+    }
+
+    let hrid = Hrid::try_from(parent).ok()?;
+    let current = tree.find_by_hrid(&hrid).map(Requirement::uuid);
+
+    parents
+        .find(|&(parent_id, _)| Some(parent_id) == current)
+        .or_else(|| requirement.parents().find(|(_, link)| link.hrid == hrid))
+        .map(|(parent_id, _)| parent_id)
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("failed to add requirement: {0}")]
+pub enum AddRequirementError {
+    Kind(#[from] EmptyStringError),
+    Disallowed(#[from] DisallowedKindError),
+    Attribute(#[from] SchemaError),
+    Io(#[from] io::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum LinkRequirementError {
+    #[error("requirement {0} not found")]
+    NotFound(Hrid),
+
+    #[error(transparent)]
+    Link(#[from] LinkError),
+
+    #[error(transparent)]
+    Disallowed(#[from] DisallowedParentError),
+
+    #[error(transparent)]
+    Relation(#[from] UnknownRelationError),
+
+    #[error("failed to save requirement: {0}")]
+    Io(#[from] io::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReviewError {
+    #[error("requirement {0} not found")]
+    NotFound(Hrid),
+
+    #[error("{parent} is not a parent of {child}")]
+    NotLinked { child: Hrid, parent: Hrid },
+
+    #[error("failed to save requirement: {0}")]
+    Io(#[from] io::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RenameRequirementError {
+    #[error("requirement {0} not found")]
+    NotFound(Hrid),
+
+    #[error(transparent)]
+    Disallowed(#[from] DisallowedKindError),
+
+    #[error(transparent)]
+    DisallowedParent(#[from] DisallowedParentError),
+
+    #[error(transparent)]
+    Rename(#[from] RenameError),
+
+    #[error("failed to save requirement: {0}")]
+    Io(#[from] io::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DeleteRequirementError {
+    #[error("requirement {0} not found")]
+    NotFound(Hrid),
+
+    #[error(transparent)]
+    Delete(#[from] DeleteError),
+
+    #[error(transparent)]
+    Disallowed(#[from] DisallowedParentError),
+
+    #[error("failed to save requirement: {0}")]
+    Io(#[from] io::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum UpdateRequirementError {
+    #[error(transparent)]
+    Replace(#[from] ReplaceError),
+
+    #[error(transparent)]
+    Disallowed(#[from] DisallowedParentError),
+
+    #[error(transparent)]
+    Attribute(#[from] SchemaError),
+
+    #[error("failed to save requirement: {0}")]
+    Io(#[from] io::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum MigrateDigitsError {
+    #[error(transparent)]
+    Rename(#[from] RenameError),
+
+    #[error("failed to parse config file: {0}")]
+    Config(#[from] toml_edit::TomlError),
+
+    #[error("failed to migrate requirements: {0}")]
+    Io(#[from] io::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SetTagsError {
+    #[error("requirement {0} not found")]
+    NotFound(Hrid),
+
+    #[error("failed to save requirement: {0}")]
+    Io(#[from] io::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum UnlinkRequirementError {
+    #[error("requirement {0} not found")]
+    NotFound(Hrid),
+
+    #[error("{parent} is not a parent of {child}")]
+    NotLinked { child: Hrid, parent: String },
+
+    #[error("failed to save requirement: {0}")]
+    Io(#[from] io::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub struct UpdateHridsError {
+    failures: NonEmpty<(PathBuf, io::Error)>,
+}
+
+impl fmt::Display for UpdateHridsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const MAX_DISPLAY: usize = 5;
+
+        write!(f, "failed to update HRIDS: ")?;
+
+        let total = self.failures.len();
+
+        let displayed_paths: Vec<String> = self
+            .failures
+            .iter()
+            .take(MAX_DISPLAY)
+            .map(|(p, _e)| p.display().to_string())
+            .collect();
+
+        let msg = displayed_paths.join(", ");
+
+        if total <= MAX_DISPLAY {
+            write!(f, "{msg}")
+        } else {
+            write!(f, "{msg}... (and {} more)", total - MAX_DISPLAY)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::{domain::requirement::Parent, Requirement};
+
+    /// Runs git in a directory, panicking if it fails.
+    fn run_git(dir: &Path, args: &[&str]) {
+        let status = std::process::Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    fn setup_temp_directory() -> (TempDir, Directory<Loaded>) {
+        let tmp = TempDir::new().expect("failed to create temp dir");
+        let path = tmp.path().to_path_buf();
+        (tmp, Directory::new(path).load_all().unwrap())
+    }
+
+    #[test]
+    fn can_add_requirement() {
+        let (_tmp, mut dir) = setup_temp_directory();
+        let r1 = dir.add_requirement("REQ".to_string()).unwrap();
+
+        assert_eq!(r1.hrid().to_string(), "REQ-001");
+
+        let loaded =
+            Requirement::load(&dir.root, r1.hrid().clone()).expect("should load saved requirement");
+        assert_eq!(loaded.uuid(), r1.uuid());
+    }
+
+    #[test]
+    fn can_add_multiple_requirements_with_incrementing_id() {
+        let (_tmp, mut dir) = setup_temp_directory();
+        let r1 = dir.add_requirement("REQ".to_string()).unwrap();
+        let r2 = dir.add_requirement("REQ".to_string()).unwrap();
+
+        assert_eq!(r1.hrid().to_string(), "REQ-001");
+        assert_eq!(r2.hrid().to_string(), "REQ-002");
+    }
+
+    #[test]
+    fn add_requirement_uses_configured_digits() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::write(
+            tmp.path().join("config.toml"),
+            "_version = \"1\"\ndigits = 4\n",
+        )
+        .unwrap();
+        let mut dir = Directory::new(tmp.path().to_path_buf()).load_all().unwrap();
+
+        let requirement = dir.add_requirement("REQ".to_string()).unwrap();
+        assert_eq!(requirement.hrid().to_string(), "REQ-0001");
+        assert!(tmp.path().join("REQ-0001.md").exists());
+    }
+
+    #[test]
+    fn migrate_digits_renames_files_and_references() {
+        let (tmp, mut dir) = setup_temp_directory();
+        let parent = dir.add_requirement("USR".to_string()).unwrap();
+        let child = dir.add_requirement("SYS".to_string()).unwrap();
+        dir.link_requirement(child.hrid().clone(), parent.hrid().clone())
+            .unwrap();
+
+        let renamed = dir.migrate_digits(4).unwrap();
+        assert_eq!(renamed.len(), 2);
+        assert!(!tmp.path().join("USR-001.md").exists());
+        assert!(!tmp.path().join("SYS-001.md").exists());
+
+        // The new width is used when the directory is reloaded
+        let mut dir = Directory::new(tmp.path().to_path_buf()).load_all().unwrap();
+        assert_eq!(dir.config().digits(), 4);
+        let child = dir.tree().find_by_hrid(child.hrid()).unwrap();
+        assert_eq!(child.hrid().to_string(), "SYS-0001");
+        let (_, link) = child.parents().next().unwrap();
+        assert_eq!(link.hrid.to_string(), "USR-0001");
+
+        let added = dir.add_requirement("USR".to_string()).unwrap();
+        assert_eq!(added.hrid().to_string(), "USR-0002");
+
+        // Nothing is changed if a new name is taken, or the config is invalid
+        std::fs::write(tmp.path().join("SYS-00001.md"), "").unwrap();
+        assert!(matches!(
+            dir.migrate_digits(5),
+            Err(MigrateDigitsError::Rename(RenameError::Taken(_)))
+        ));
+        std::fs::remove_file(tmp.path().join("SYS-00001.md")).unwrap();
+        std::fs::write(tmp.path().join("config.toml"), "digits = ").unwrap();
+        assert!(dir.migrate_digits(5).is_err());
+        assert!(tmp.path().join("SYS-0001.md").exists());
+        assert!(!tmp.path().join("USR-00001.md").exists());
+        assert_eq!(dir.config().digits(), 4);
+    }
+
+    #[test]
+    fn config_with_digits_keeps_comments() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("config.toml");
+        let config = Config::default().with_digits(5);
+
+        assert_eq!(
+            config_with_digits(&path, &config).unwrap(),
+            config.to_commented_toml()
+        );
+
+        std::fs::write(&path, Config::default().to_commented_toml()).unwrap();
+        let contents = config_with_digits(&path, &config).unwrap();
+        assert!(contents.contains("\ndigits = 5\n"));
+        assert!(contents.contains("# allowed_kinds"));
+        std::fs::write(&path, contents).unwrap();
+        assert_eq!(Config::load(&path).unwrap(), config);
+
+        // A file without the setting has it added before any tables
+        std::fs::write(
+            &path,
+            "_version = \"1\"\nallow_invalid = true\n\n[relations.verifies]\n",
+        )
+        .unwrap();
+        std::fs::write(&path, config_with_digits(&path, &config).unwrap()).unwrap();
+        assert_eq!(Config::load(&path).unwrap().digits(), 5);
+
+        std::fs::write(&path, "digits = \n").unwrap();
+        assert!(matches!(
+            config_with_digits(&path, &config),
+            Err(MigrateDigitsError::Config(_))
+        ));
+    }
+
+    #[test]
+    fn allowed_kinds_are_enforced() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path().to_path_buf();
+        let mut dir = Directory::new(root.clone()).load_all().unwrap();
+        dir.add_requirement("USR".to_string()).unwrap();
+        dir.add_requirement("SYSS".to_string()).unwrap();
+
+        std::fs::write(
+            root.join("config.toml"),
+            "_version = \"1\"\nallowed_kinds = [\"USR\", \"SYS\"]\n",
+        )
+        .unwrap();
+        let mut dir = Directory::new(root).load_all().unwrap();
+
+        let disallowed = dir.disallowed_kinds();
+        assert_eq!(disallowed.len(), 1);
+        assert_eq!(disallowed[0].0.to_string(), "SYSS-001");
+        assert_eq!(disallowed[0].1.suggestion.as_deref(), Some("SYS"));
+
+        assert!(matches!(
+            dir.add_requirement("SYSS".to_string()),
+            Err(AddRequirementError::Disallowed(_))
+        ));
+        dir.add_requirement("SYS".to_string()).unwrap();
+
+        let usr = Hrid::try_from("USR-001").unwrap();
+        assert!(matches!(
+            dir.rename_requirement(&usr, Hrid::try_from("TST-009").unwrap()),
+            Err(RenameRequirementError::Disallowed(_))
+        ));
+        assert!(dir.path(&usr).exists());
+        dir.rename_requirement(&usr, Hrid::try_from("SYS-009").unwrap())
+            .unwrap();
+    }
+
+    #[test]
+    fn load_all_reports_invalid_config() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path().to_path_buf();
+        std::fs::write(
+            root.join("config.toml"),
+            "_version = \"1\"\nallowed_kinds = [\"USR\", \"SYS\"]\n\n[[coverage]]\nkind = \
+             \"USR\"\ncoverd_by = \"SYS\"\n",
+        )
+        .unwrap();
+
+        let Err(DirectoryLoadError::Config { path, error }) = Directory::new(root).load_all()
+        else {
+            panic!("expected a config error");
+        };
+        assert!(path.ends_with("config.toml"));
+        let ConfigError::Parse(error) = &error else {
+            panic!("expected a parse error");
+        };
+        assert!(error.to_string().contains("covered_by"));
+        assert!(error.span().is_some());
+
+        std::fs::write(
+            tmp.path().join("config.toml"),
+            "_version = \"1\"\n[attributes.priority]\ntype = \"enum\"\nvalues = [\"low\", \
+             \"high\"]\ndefault = \"hgh\"\n",
+        )
+        .unwrap();
+        let Err(DirectoryLoadError::Config { error, .. }) =
+            Directory::new(tmp.path().to_path_buf()).load_all()
+        else {
+            panic!("expected a config error");
+        };
+        assert_eq!(
+            error.to_string(),
+            "invalid default in config file: attribute 'priority' must be one of low, high, but \
+             is 'hgh' (did you mean 'high'?)"
+        );
+    }
+
+    #[test]
+    fn load_all_reports_every_invalid_file() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path().to_path_buf();
+        std::fs::write(root.join("README.md"), "# Requirements").unwrap();
+        std::fs::write(root.join("USR-001.md"), "no frontmatter").unwrap();
+        std::fs::write(
+            root.join("USR-002.md"),
+            "---\n_version: '1'\nuuid: not-a-uuid\n---\n",
+        )
+        .unwrap();
+
+        let Err(DirectoryLoadError::InvalidFiles(files)) = Directory::new(root).load_all() else {
+            panic!("expected invalid files");
+        };
+        assert_eq!(files.len(), 3);
+        assert!(matches!(files[0].kind, InvalidFileKind::Unrecognised));
+        assert!(matches!(
+            files[1].kind,
+            InvalidFileKind::Invalid(LoadError::MissingFrontMatter)
+        ));
+        let InvalidFileKind::Invalid(LoadError::Yaml(error)) = &files[2].kind else {
+            panic!("expected a YAML error");
+        };
+        assert_eq!(error.location().unwrap().line(), 3);
+
+        let message = DirectoryLoadError::InvalidFiles(files).to_string();
+        assert!(message.contains("README.md: file name is not an HRID"));
+        assert!(message.contains("USR-001.md: missing frontmatter"));
+    }
+
+    #[test]
+    fn load_all_reports_duplicate_hrids() {
+        let (tmp, mut dir) = setup_temp_directory();
+        dir.add_requirement("SYS".to_string()).unwrap();
+        dir.add_requirement("SYS".to_string()).unwrap();
+        std::fs::rename(tmp.path().join("SYS-002.md"), tmp.path().join("SYS-1.md")).unwrap();
+
+        // Duplicates can't be skipped, since either file could be the right one
+        std::fs::write(
+            tmp.path().join("config.toml"),
+            "_version = \"1\"\nallow_invalid = true\n",
+        )
+        .unwrap();
+
+        let Err(DirectoryLoadError::InvalidFiles(files)) =
+            Directory::new(tmp.path().to_path_buf()).load_all()
+        else {
+            panic!("expected invalid files");
+        };
+        assert_eq!(files.len(), 2);
+        assert!(files[0].path.ends_with("SYS-001.md"));
+        assert!(matches!(
+            &files[0].kind,
+            InvalidFileKind::DuplicateHrid(other) if other.ends_with("SYS-1.md")
+        ));
+        assert!(files[1].path.ends_with("SYS-1.md"));
+
+        let message = DirectoryLoadError::InvalidFiles(files).to_string();
+        assert!(message.contains("SYS-001.md: the HRID is also used by"));
+        assert!(!message.contains("allow_invalid"));
+    }
+
+    #[test]
+    fn allow_unrecognised_and_allow_invalid_are_separate() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path().to_path_buf();
+        std::fs::write(root.join("README.md"), "# Requirements").unwrap();
+        std::fs::write(root.join("USR-001.md"), "no frontmatter").unwrap();
+
+        let config = |settings: &str| {
+            std::fs::write(
+                root.join("config.toml"),
+                format!("_version = \"1\"\n{settings}"),
+            )
+            .unwrap();
+            Directory::new(root.clone()).load_all()
+        };
+
+        let Err(DirectoryLoadError::InvalidFiles(files)) = config("allow_unrecognised = true")
+        else {
+            panic!("expected invalid files");
+        };
+        assert_eq!(files.len(), 1);
+        assert!(files[0].path.ends_with("USR-001.md"));
+
+        let Err(DirectoryLoadError::InvalidFiles(files)) = config("allow_invalid = true") else {
+            panic!("expected invalid files");
+        };
+        assert_eq!(files.len(), 1);
+        assert!(files[0].path.ends_with("README.md"));
+
+        let dir = config("allow_unrecognised = true\nallow_invalid = true").unwrap();
+        assert_eq!(dir.tree().requirements().count(), 0);
+    }
+
+    #[test]
+    fn attributes_are_checked_on_load() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path().to_path_buf();
+        std::fs::write(
+            root.join("config.toml"),
+            "_version = \"1\"\n[attributes.priority]\ntype = \"enum\"\nvalues = [\"low\", \
+             \"high\"]\ndefault = \"low\"\n[attributes.owner]\ntype = \"string\"\nrequired = \
+             true\nfingerprint = false\n",
+        )
+        .unwrap();
+        let mut dir = Directory::new(root.clone()).load_all().unwrap();
+
+        let Err(AddRequirementError::Attribute(error)) = dir.add_requirement("USR".to_string())
+        else {
+            panic!("expected an attribute error");
+        };
+        assert_eq!(
+            error.errors(),
+            [AttributeError::Missing("owner".to_string())]
+        );
+        let usr = dir
+            .add_requirement_with_attributes(
+                "USR".to_string(),
+                BTreeMap::from([(
+                    "owner".to_string(),
+                    AttributeValue::String("alice".to_string()),
+                )]),
+            )
+            .unwrap();
+
+        // Defaults are filled in, but not saved
+        let dir = Directory::new(root.clone()).load_all().unwrap();
+        let loaded = dir.tree().find_by_hrid(usr.hrid()).unwrap();
+        assert_eq!(
+            loaded
+                .attribute("priority")
+                .and_then(AttributeValue::as_str),
+            Some("low")
+        );
+        let contents = std::fs::read_to_string(dir.path(usr.hrid())).unwrap();
+        assert!(contents.contains("owner: alice"));
+        assert!(!contents.contains("priority"));
+
+        // Attributes which don't contribute to the fingerprint can change
+        // without making links suspect
+        let mut edited = loaded.clone();
+        edited.set_attribute(
+            "owner".to_string(),
+            AttributeValue::String("bob".to_string()),
+        );
+        assert_eq!(edited.fingerprint(), loaded.fingerprint());
+        edited.set_attribute(
+            "priority".to_string(),
+            AttributeValue::String("high".to_string()),
+        );
+        assert_ne!(edited.fingerprint(), loaded.fingerprint());
+
+        // Missing required attributes are reported, but don't stop the
+        // directory from loading
+        std::fs::write(dir.path(usr.hrid()), contents.replace("owner: alice", "")).unwrap();
+        let dir = Directory::new(root.clone()).load_all().unwrap();
+        assert_eq!(
+            dir.missing_attributes(),
+            vec![(
+                usr.hrid().clone(),
+                AttributeError::Missing("owner".to_string())
+            )]
+        );
+        let loaded = dir.tree().find_by_hrid(usr.hrid()).unwrap().clone();
+        assert!(matches!(
+            dir.check_update(&loaded),
+            Err(UpdateRequirementError::Attribute(_))
+        ));
+
+        // Every attribute which doesn't match is reported
+        std::fs::write(
+            dir.path(usr.hrid()),
+            contents.replace("owner: alice", "owner: 3\n  priorty: high"),
+        )
+        .unwrap();
+        let Err(DirectoryLoadError::InvalidFiles(files)) = Directory::new(root).load_all() else {
+            panic!("expected invalid files");
+        };
+        assert_eq!(
+            files[0].kind.to_string(),
+            "attribute 'owner' must be a string, but is '3'; attribute 'priorty' is not declared \
+             (did you mean 'priority'?)"
+        );
+    }
+
+    #[test]
+    fn parent_kinds_are_enforced() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path().to_path_buf();
+        let mut dir = Directory::new(root.clone()).load_all().unwrap();
+        let usr = dir.add_requirement("USR".to_string()).unwrap();
+        let sys = dir.add_requirement("SYS".to_string()).unwrap();
+        let tst = dir.add_requirement("TST".to_string()).unwrap();
+        dir.link_requirement(tst.hrid().clone(), usr.hrid().clone())
+            .unwrap();
+
+        std::fs::write(
+            root.join("config.toml"),
+            "_version = \"1\"\n[parent_kinds]\nSYS = [\"USR\"]\nTST = [\"SYS\"]\n",
+        )
+        .unwrap();
+        let mut dir = Directory::new(root).load_all().unwrap();
+
+        let disallowed = dir.disallowed_parents();
+        assert_eq!(disallowed.len(), 1);
+        assert_eq!(&disallowed[0].0, tst.hrid());
+        assert_eq!(&disallowed[0].1, usr.hrid());
+
+        assert!(matches!(
+            dir.link_requirement(sys.hrid().clone(), tst.hrid().clone()),
+            Err(LinkRequirementError::Disallowed(_))
+        ));
+        dir.link_requirement(sys.hrid().clone(), usr.hrid().clone())
+            .unwrap();
+        dir.link_requirement(tst.hrid().clone(), sys.hrid().clone())
+            .unwrap();
+
+        // A TST may not keep SYS-001's parent USR-001
+        assert!(matches!(
+            dir.rename_requirement(sys.hrid(), Hrid::try_from("TST-009").unwrap()),
+            Err(RenameRequirementError::DisallowedParent(_))
+        ));
+        assert!(matches!(
+            dir.delete_requirement(sys.hrid(), DeletePolicy::Reparent),
+            Err(DeleteRequirementError::Disallowed(_))
+        ));
+        assert!(dir.path(sys.hrid()).exists());
+
+        let mut edited = dir.add_requirement("SYS".to_string()).unwrap();
+        edited.add_parent(
+            tst.uuid(),
+            Parent {
+                hrid: tst.hrid().clone(),
+                fingerprint: tst.fingerprint(),
+                relation: None,
+            },
+        );
+        assert!(matches!(
+            dir.update_requirement(edited),
+            Err(UpdateRequirementError::Disallowed(_))
+        ));
+    }
+
+    #[test]
+    fn links_can_have_declared_relations() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path().to_path_buf();
+        std::fs::write(
+            root.join("config.toml"),
+            "_version = \"1\"\n[relations.verifies]\n",
+        )
+        .unwrap();
+        let mut dir = Directory::new(root.clone()).load_all().unwrap();
+        let sys = dir.add_requirement("SYS".to_string()).unwrap();
+        let tst = dir.add_requirement("TST".to_string()).unwrap();
+
+        assert!(matches!(
+            dir.link_requirement_as(
+                tst.hrid().clone(),
+                sys.hrid().clone(),
+                Some("verfies".to_string())
+            ),
+            Err(LinkRequirementError::Relation(_))
+        ));
+        dir.link_requirement_as(
+            tst.hrid().clone(),
+            sys.hrid().clone(),
+            Some("verifies".to_string()),
+        )
+        .unwrap();
+
+        // Linking again without a relation keeps the existing one
+        dir.link_requirement(tst.hrid().clone(), sys.hrid().clone())
+            .unwrap();
+
+        let reloaded = Requirement::load(&root, tst.hrid().clone()).unwrap();
+        let (_, parent) = reloaded.parents().next().unwrap();
+        assert_eq!(parent.relation.as_deref(), Some("verifies"));
+        assert!(dir.unknown_relations().is_empty());
+
+        std::fs::write(root.join("config.toml"), "_version = \"1\"\n").unwrap();
+        let dir = Directory::new(root).load_all().unwrap();
+        let unknown = dir.unknown_relations();
+        assert_eq!(unknown.len(), 1);
+        assert_eq!(&unknown[0].0, tst.hrid());
+        assert_eq!(unknown[0].2.relation, "verifies");
+    }
+
+    #[test]
+    fn can_link_two_requirements() {
+        let (_tmp, mut dir) = setup_temp_directory();
+        let parent = dir.add_requirement("SYS".to_string()).unwrap();
+        let child = dir.add_requirement("USR".to_string()).unwrap();
+
+        dir.link_requirement(child.hrid().clone(), parent.hrid().clone())
+            .unwrap();
+
+        let updated =
             Requirement::load(&dir.root, child.hrid().clone()).expect("should load child");
 
         let parents: Vec<_> = updated.parents().collect();
@@ -325,6 +1884,340 @@ mod tests {
         assert_eq!(&parents[0].1.hrid, parent.hrid());
     }
 
+    #[test]
+    fn link_requirement_rejects_cycles() {
+        let (_tmp, mut dir) = setup_temp_directory();
+        let a = dir.add_requirement("A".to_string()).unwrap();
+        let b = dir.add_requirement("B".to_string()).unwrap();
+        let c = dir.add_requirement("C".to_string()).unwrap();
+
+        dir.link_requirement(a.hrid().clone(), b.hrid().clone())
+            .unwrap();
+        dir.link_requirement(b.hrid().clone(), c.hrid().clone())
+            .unwrap();
+
+        let result = dir.link_requirement(c.hrid().clone(), a.hrid().clone());
+        assert!(matches!(
+            result,
+            Err(LinkRequirementError::Link(LinkError::Cycle(_)))
+        ));
+
+        // The rejected link must not have been written to disk
+        let reloaded = Requirement::load(&dir.root, c.hrid().clone()).unwrap();
+        assert_eq!(reloaded.parents().count(), 0);
+        assert!(Directory::new(dir.root.clone())
+            .load_all()
+            .unwrap()
+            .find_cycles()
+            .is_empty());
+    }
+
+    #[test]
+    fn suspect_links_are_detected_after_parent_changes() {
+        let (_tmp, mut dir) = setup_temp_directory();
+        let parent = dir.add_requirement("SYS".to_string()).unwrap();
+        let child = dir.add_requirement("USR".to_string()).unwrap();
+        dir.link_requirement(child.hrid().clone(), parent.hrid().clone())
+            .unwrap();
+        assert!(dir.suspect_links().is_empty());
+
+        let mut edited = parent.clone();
+        edited.add_tag("changed".to_string());
+        edited.save(&dir.root).unwrap();
+
+        let suspect_links = Directory::new(dir.root.clone())
+            .load_all()
+            .unwrap()
+            .suspect_links();
+        assert_eq!(suspect_links.len(), 1);
+        assert_eq!(&suspect_links[0].child_hrid, child.hrid());
+        assert_eq!(&suspect_links[0].parent_hrid, parent.hrid());
+    }
+
+    #[test]
+    fn changed_since_compares_fingerprints_with_git() {
+        let (tmp, mut dir) = setup_temp_directory();
+        run_git(tmp.path(), &["init", "--quiet"]);
+
+        let usr = dir.add_requirement("USR".to_string()).unwrap();
+        let sys = dir.add_requirement("SYS".to_string()).unwrap();
+        run_git(tmp.path(), &["add", "."]);
+        run_git(tmp.path(), &["commit", "--quiet", "-m", "initial"]);
+        assert!(dir.changed_since("HEAD").unwrap().is_empty());
+
+        // Only the fingerprint matters, not the formatting of the file
+        dir.link_requirement(sys.hrid().clone(), usr.hrid().clone())
+            .unwrap();
+        let mut edited = usr.clone();
+        edited.set_content("edited".to_string());
+        dir.update_requirement(edited).unwrap();
+
+        // New files are changed, even if git doesn't track them yet
+        let tst = dir.add_requirement("TST".to_string()).unwrap();
+
+        assert_eq!(
+            dir.changed_since("HEAD").unwrap(),
+            vec![tst.hrid().clone(), usr.hrid().clone()]
+        );
+        assert!(matches!(
+            dir.changed_since("no-such-revision"),
+            Err(GitError::Failed { .. })
+        ));
+    }
+
+    #[test]
+    fn changed_since_finds_files_with_non_ascii_paths() {
+        let (tmp, mut dir) = setup_temp_directory();
+        run_git(tmp.path(), &["init", "--quiet"]);
+
+        // Git quotes paths like this one unless it is told not to
+        let sys = dir.add_requirement("SYS".to_string()).unwrap();
+        let namespace = tmp.path().join("système");
+        std::fs::create_dir(&namespace).unwrap();
+        std::fs::rename(dir.path(sys.hrid()), namespace.join("SYS-001.md")).unwrap();
+        run_git(tmp.path(), &["add", "."]);
+        run_git(tmp.path(), &["commit", "--quiet", "-m", "initial"]);
+
+        let mut edited = sys.clone();
+        edited.set_content("edited".to_string());
+        edited.save(&namespace).unwrap();
+
+        let dir = Directory::new(dir.root).load_all().unwrap();
+        assert_eq!(dir.changed_since("HEAD").unwrap(), vec![sys.hrid().clone()]);
+    }
+
+    #[test]
+    fn review_clears_suspect_links() {
+        let (_tmp, mut dir) = setup_temp_directory();
+        let parent = dir.add_requirement("SYS".to_string()).unwrap();
+        let child = dir.add_requirement("USR".to_string()).unwrap();
+        dir.link_requirement(child.hrid().clone(), parent.hrid().clone())
+            .unwrap();
+
+        let mut edited = parent.clone();
+        edited.add_tag("changed".to_string());
+        edited.save(&dir.root).unwrap();
+
+        let mut dir = Directory::new(dir.root.clone()).load_all().unwrap();
+        let reviewed = dir.review(child.hrid(), Some(parent.hrid())).unwrap();
+        assert_eq!(reviewed, vec![parent.hrid().clone()]);
+
+        let reloaded = Directory::new(dir.root.clone()).load_all().unwrap();
+        assert!(reloaded.suspect_links().is_empty());
+    }
+
+    #[test]
+    fn unlink_requirement_removes_links_to_deleted_parents() {
+        let (_tmp, mut dir) = setup_temp_directory();
+        let usr = dir.add_requirement("USR".to_string()).unwrap();
+        let sys = dir.add_requirement("SYS".to_string()).unwrap();
+        let child = dir.add_requirement("TST".to_string()).unwrap();
+        dir.link_requirement(child.hrid().clone(), usr.hrid().clone())
+            .unwrap();
+        dir.link_requirement(child.hrid().clone(), sys.hrid().clone())
+            .unwrap();
+
+        std::fs::remove_file(dir.path(usr.hrid())).unwrap();
+        let mut dir = Directory::new(dir.root.clone()).load_all().unwrap();
+
+        dir.unlink_requirement(child.hrid(), "USR-001").unwrap();
+        dir.unlink_requirement(child.hrid(), &sys.uuid().to_string())
+            .unwrap();
+
+        assert!(matches!(
+            dir.unlink_requirement(child.hrid(), "SYS-001"),
+            Err(UnlinkRequirementError::NotLinked { .. })
+        ));
+
+        let reloaded = Requirement::load(&dir.root, child.hrid().clone()).unwrap();
+        assert_eq!(reloaded.parents().count(), 0);
+    }
+
+    #[test]
+    fn rename_requirement_moves_file_and_updates_children() {
+        let (_tmp, mut dir) = setup_temp_directory();
+        let parent = dir.add_requirement("USR".to_string()).unwrap();
+        let child = dir.add_requirement("SYS".to_string()).unwrap();
+        let other = dir.add_requirement("SYS".to_string()).unwrap();
+        dir.link_requirement(child.hrid().clone(), parent.hrid().clone())
+            .unwrap();
+
+        assert!(matches!(
+            dir.rename_requirement(parent.hrid(), other.hrid().clone()),
+            Err(RenameRequirementError::Rename(RenameError::Taken(_)))
+        ));
+
+        let new = Hrid::try_from("AUTH-USR-001").unwrap();
+        let modified = dir.rename_requirement(parent.hrid(), new.clone()).unwrap();
+        assert_eq!(modified, vec![child.hrid().clone()]);
+
+        assert!(!dir.path(parent.hrid()).exists());
+
+        let reloaded = Directory::new(dir.root.clone()).load_all().unwrap();
+        let renamed = reloaded.tree().find_by_hrid(&new).unwrap();
+        assert_eq!(renamed.uuid(), parent.uuid());
+
+        let child = reloaded.tree().find_by_hrid(child.hrid()).unwrap();
+        let (_, link) = child.parents().next().unwrap();
+        assert_eq!(link.hrid, new);
+        assert!(reloaded.suspect_links().is_empty());
+    }
+
+    #[test]
+    fn delete_requirement_detaches_children() {
+        let (_tmp, mut dir) = setup_temp_directory();
+        let parent = dir.add_requirement("USR".to_string()).unwrap();
+        let child = dir.add_requirement("SYS".to_string()).unwrap();
+        dir.link_requirement(child.hrid().clone(), parent.hrid().clone())
+            .unwrap();
+
+        assert!(matches!(
+            dir.delete_requirement(parent.hrid(), DeletePolicy::Refuse),
+            Err(DeleteRequirementError::Delete(
+                DeleteError::HasChildren { .. }
+            ))
+        ));
+        assert!(dir.path(parent.hrid()).exists());
+
+        let modified = dir
+            .delete_requirement(parent.hrid(), DeletePolicy::Detach)
+            .unwrap();
+        assert_eq!(modified, vec![child.hrid().clone()]);
+        assert!(!dir.path(parent.hrid()).exists());
+
+        let reloaded = Directory::new(dir.root.clone()).load_all().unwrap();
+        assert!(reloaded.tree().find_by_hrid(parent.hrid()).is_none());
+        assert!(reloaded.broken_links().is_empty());
+    }
+
+    #[test]
+    fn delete_requirement_leaves_children_if_the_file_cannot_be_removed() {
+        let (_tmp, mut dir) = setup_temp_directory();
+        let parent = dir.add_requirement("USR".to_string()).unwrap();
+        let child = dir.add_requirement("SYS".to_string()).unwrap();
+        dir.link_requirement(child.hrid().clone(), parent.hrid().clone())
+            .unwrap();
+
+        std::fs::remove_file(dir.path(parent.hrid())).unwrap();
+        assert!(matches!(
+            dir.delete_requirement(parent.hrid(), DeletePolicy::Detach),
+            Err(DeleteRequirementError::Io(_))
+        ));
+
+        let reloaded = Requirement::load(&dir.root, child.hrid().clone()).unwrap();
+        assert_eq!(reloaded.parents().count(), 1);
+        assert!(dir.tree().find_by_hrid(parent.hrid()).is_some());
+    }
+
+    #[test]
+    fn requirements_stay_in_their_subdirectories() {
+        let (tmp, mut dir) = setup_temp_directory();
+        let parent = dir.add_requirement("USR".to_string()).unwrap();
+        let child = dir.add_requirement("SYS".to_string()).unwrap();
+
+        let sub = tmp.path().join("sub");
+        std::fs::create_dir(&sub).unwrap();
+        for hrid in [parent.hrid(), child.hrid()] {
+            std::fs::rename(dir.path(hrid), sub.join(format!("{hrid}.md"))).unwrap();
+        }
+
+        let mut dir = Directory::new(tmp.path().to_path_buf()).load_all().unwrap();
+        assert_eq!(dir.path(child.hrid()), sub.join("SYS-001.md"));
+
+        dir.link_requirement(child.hrid().clone(), parent.hrid().clone())
+            .unwrap();
+        assert!(!tmp.path().join("SYS-001.md").exists());
+        let reloaded = Requirement::load(&sub, child.hrid().clone()).unwrap();
+        assert_eq!(reloaded.parents().count(), 1);
+
+        let new = Hrid::try_from("USR-002").unwrap();
+        dir.rename_requirement(parent.hrid(), new.clone()).unwrap();
+        assert_eq!(dir.path(&new), sub.join("USR-002.md"));
+        assert!(sub.join("USR-002.md").exists());
+        assert!(!sub.join("USR-001.md").exists());
+
+        dir.delete_requirement(&new, DeletePolicy::Detach).unwrap();
+        assert!(!sub.join("USR-002.md").exists());
+        let reloaded = Requirement::load(&sub, child.hrid().clone()).unwrap();
+        assert_eq!(reloaded.parents().count(), 0);
+
+        // Broken links are reported against the file in the subdirectory
+        let other = dir.add_requirement("USR".to_string()).unwrap();
+        dir.link_requirement(child.hrid().clone(), other.hrid().clone())
+            .unwrap();
+        std::fs::remove_file(dir.path(other.hrid())).unwrap();
+
+        let mut dir = Directory::new(tmp.path().to_path_buf()).load_all().unwrap();
+        let broken_links = dir.broken_links();
+        assert_eq!(broken_links.len(), 1);
+        assert_eq!(broken_links[0].0, sub.join("SYS-001.md"));
+
+        let pruned = dir.prune_broken_links().unwrap();
+        assert_eq!(pruned[0].0, sub.join("SYS-001.md"));
+        let reloaded = Requirement::load(&sub, child.hrid().clone()).unwrap();
+        assert_eq!(reloaded.parents().count(), 0);
+    }
+
+    #[test]
+    fn set_tags_reports_suspect_children() {
+        let (_tmp, mut dir) = setup_temp_directory();
+        let parent = dir.add_requirement("USR".to_string()).unwrap();
+        let child = dir.add_requirement("SYS".to_string()).unwrap();
+        dir.link_requirement(child.hrid().clone(), parent.hrid().clone())
+            .unwrap();
+
+        let tags = BTreeSet::from(["safety".to_string()]);
+        let suspect = dir.set_tags(parent.hrid(), tags.clone()).unwrap();
+        assert_eq!(suspect, vec![child.hrid().clone()]);
+
+        let reloaded = Requirement::load(&dir.root, parent.hrid().clone()).unwrap();
+        assert_eq!(*reloaded.tags(), tags);
+        assert_eq!(dir.suspect_links().len(), 1);
+    }
+
+    #[test]
+    fn update_requirement_saves_and_reports_suspect_children() {
+        let (_tmp, mut dir) = setup_temp_directory();
+        let parent = dir.add_requirement("USR".to_string()).unwrap();
+        let child = dir.add_requirement("SYS".to_string()).unwrap();
+        dir.link_requirement(child.hrid().clone(), parent.hrid().clone())
+            .unwrap();
+
+        let mut edited = parent.clone();
+        edited.set_content("edited".to_string());
+        let suspect = dir.update_requirement(edited).unwrap();
+        assert_eq!(suspect, vec![child.hrid().clone()]);
+
+        let reloaded = Requirement::load(&dir.root, parent.hrid().clone()).unwrap();
+        assert_eq!(reloaded.content(), "edited");
+    }
+
+    #[test]
+    fn review_all_filters_by_kind() {
+        let (_tmp, mut dir) = setup_temp_directory();
+        let parent = dir.add_requirement("USR".to_string()).unwrap();
+        let sys = dir.add_requirement("SYS".to_string()).unwrap();
+        let tst = dir.add_requirement("TST".to_string()).unwrap();
+        dir.link_requirement(sys.hrid().clone(), parent.hrid().clone())
+            .unwrap();
+        dir.link_requirement(tst.hrid().clone(), parent.hrid().clone())
+            .unwrap();
+
+        let mut parent = parent;
+        parent.add_tag("changed".to_string());
+        parent.save(&dir.root).unwrap();
+
+        let mut dir = Directory::new(dir.root.clone()).load_all().unwrap();
+        let reviewed = dir.review_all(Some("SYS"), &[]).unwrap();
+        assert_eq!(reviewed.len(), 1);
+        assert_eq!(&reviewed[0].child_hrid, sys.hrid());
+
+        let reloaded = Directory::new(dir.root.clone()).load_all().unwrap();
+        let remaining = reloaded.suspect_links();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(&remaining[0].child_hrid, tst.hrid());
+    }
+
     #[test]
     fn update_hrids_corrects_outdated_parent_hrids() {
         let (_tmp, mut dir) = setup_temp_directory();
@@ -337,6 +2230,7 @@ mod tests {
             Parent {
                 hrid: Hrid::try_from("WRONG-999").unwrap(),
                 fingerprint: parent.fingerprint(),
+                relation: None,
             },
         );
         child.save(&dir.root).unwrap();
@@ -0,0 +1,150 @@
+//! Reading the history of a requirements root from git
+//!
+//! The `git` command line tool is used, so it must be installed, and the root
+//! must be inside a git repository.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// Errors that can occur when reading from git.
+#[derive(Debug, thiserror::Error)]
+pub enum GitError {
+    /// `git` could not be run.
+    #[error("failed to run git: {0}")]
+    Io(#[from] io::Error),
+
+    /// `git` ran, but failed.
+    #[error("'git {command}' failed: {message}")]
+    Failed {
+        /// The git subcommand which failed.
+        command: String,
+
+        /// What git printed to stderr.
+        message: String,
+    },
+}
+
+/// Runs git in a directory, returning its output if it succeeds.
+///
+/// Git's messages are not translated, so that errors can be recognised.
+fn git(dir: &Path, args: &[&str]) -> Result<Vec<u8>, GitError> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .env("LC_ALL", "C")
+        .output()?;
+    if !output.status.success() {
+        return Err(GitError::Failed {
+            command: args.first().copied().unwrap_or_default().to_string(),
+            message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    Ok(output.stdout)
+}
+
+/// Splits the output of a git command run with `-z` into paths.
+///
+/// Without `-z`, git quotes and escapes unusual paths.
+fn paths(output: &[u8]) -> impl Iterator<Item = PathBuf> + '_ {
+    output
+        .split(|&byte| byte == b'\0')
+        .filter(|path| !path.is_empty())
+        .map(|path| PathBuf::from(String::from_utf8_lossy(path).as_ref()))
+}
+
+/// Lists the files in a directory which have changed since a revision,
+/// relative to the directory.
+///
+/// This includes uncommitted changes, and untracked files which are not
+/// ignored.
+pub fn changed_files(dir: &Path, revision: &str) -> Result<Vec<PathBuf>, GitError> {
+    let changed = git(
+        dir,
+        &[
+            "diff",
+            "--name-only",
+            "-z",
+            "--relative",
+            revision,
+            "--",
+            ".",
+        ],
+    )?;
+    let untracked = git(
+        dir,
+        &[
+            "ls-files",
+            "-z",
+            "--others",
+            "--exclude-standard",
+            "--",
+            ".",
+        ],
+    )?;
+
+    Ok(paths(&changed).chain(paths(&untracked)).collect())
+}
+
+/// Reads a file in a directory as it was at a revision.
+///
+/// Returns `None` if the file did not exist at that revision. Any other
+/// failure, such as an unknown revision, is an error.
+pub fn show(dir: &Path, revision: &str, path: &Path) -> Result<Option<Vec<u8>>, GitError> {
+    let object = format!("{revision}:./{}", path.display());
+    match git(dir, &["show", &object]) {
+        Ok(contents) => Ok(Some(contents)),
+        Err(GitError::Failed { message, .. })
+            if message.contains("does not exist in")
+                || message.contains("exists on disk, but not in") =>
+        {
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn show_only_treats_missing_files_as_none() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+        git(dir, &["init", "--quiet"]).unwrap();
+        std::fs::write(dir.join("USR-001.md"), "old").unwrap();
+        git(dir, &["add", "."]).unwrap();
+        git(
+            dir,
+            &[
+                "-c",
+                "user.name=test",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "--quiet",
+                "-m",
+                "initial",
+            ],
+        )
+        .unwrap();
+        std::fs::write(dir.join("USR-002.md"), "new").unwrap();
+
+        assert_eq!(
+            show(dir, "HEAD", Path::new("USR-001.md")).unwrap(),
+            Some(b"old".to_vec())
+        );
+        assert_eq!(show(dir, "HEAD", Path::new("USR-002.md")).unwrap(), None);
+        assert_eq!(show(dir, "HEAD", Path::new("USR-003.md")).unwrap(), None);
+        assert!(matches!(
+            show(dir, "no-such-revision", Path::new("USR-001.md")),
+            Err(GitError::Failed { .. })
+        ));
+    }
+}
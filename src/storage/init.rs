@@ -0,0 +1,142 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::domain::Config;
+
+/// A documentation tool which the requirements can be published with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Integration {
+    /// Publish the requirements as an [mdBook](https://rust-lang.github.io/mdBook/).
+    MdBook,
+
+    /// Publish the requirements with [Sphinx](https://www.sphinx-doc.org/),
+    /// using the `MyST` markdown parser.
+    Sphinx,
+}
+
+impl Integration {
+    /// The files which integrate the requirements root with the tool, as pairs
+    /// of file name and contents.
+    const fn files(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::MdBook => &[("book.toml", MDBOOK_TOML), ("SUMMARY.md", MDBOOK_SUMMARY)],
+            Self::Sphinx => &[("conf.py", SPHINX_CONF), ("index.md", SPHINX_INDEX)],
+        }
+    }
+}
+
+const MDBOOK_TOML: &str = r#"[book]
+title = "Requirements"
+language = "en"
+# The requirements root is also the source directory of the book
+src = "."
+"#;
+
+const MDBOOK_SUMMARY: &str = "# Summary
+
+<!-- List each requirement that should appear in the book, for example:
+
+- [USR-001](USR-001.md)
+-->
+";
+
+const SPHINX_CONF: &str = r#"# Build the documentation with 'sphinx-build . _build'
+project = "Requirements"
+
+# Requirements are markdown, which Sphinx reads using the MyST parser
+extensions = ["myst_parser"]
+
+exclude_patterns = ["_build", "Thumbs.db", ".DS_Store", ".venv"]
+"#;
+
+const SPHINX_INDEX: &str = "# Requirements
+
+```{toctree}
+:maxdepth: 2
+:caption: Contents:
+:glob: true
+
+*
+```
+";
+
+/// Errors that can occur when initialising a requirements root.
+#[derive(Debug, thiserror::Error)]
+pub enum InitError {
+    #[error("{} already exists", .0.display())]
+    AlreadyExists(PathBuf),
+
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+/// Create a requirements root containing a configuration file, and the files
+/// for any integration.
+///
+/// Returns the paths of the files which were created.
+pub(super) fn init(
+    root: &Path,
+    config: &Config,
+    integration: Option<Integration>,
+) -> Result<Vec<PathBuf>, InitError> {
+    let mut files = vec![(root.join("config.toml"), config.to_commented_toml())];
+    for (name, contents) in integration.map_or(&[][..], Integration::files) {
+        files.push((root.join(name), (*contents).to_string()));
+    }
+
+    // Check everything first, so that nothing is written if anything would be
+    // overwritten
+    if let Some((path, _)) = files.iter().find(|(path, _)| path.exists()) {
+        return Err(InitError::AlreadyExists(path.clone()));
+    }
+
+    std::fs::create_dir_all(root)?;
+    for (path, contents) in &files {
+        std::fs::write(path, contents)?;
+    }
+
+    Ok(files.into_iter().map(|(path, _)| path).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn init_creates_root_and_config() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path().join("reqs");
+        let config = Config::default().with_digits(4);
+
+        let created = init(&root, &config, None).unwrap();
+        assert_eq!(created, vec![root.join("config.toml")]);
+
+        let loaded = Config::load(&root.join("config.toml")).unwrap();
+        assert_eq!(loaded, config);
+    }
+
+    #[test]
+    fn init_writes_integration_files() {
+        let tmp = TempDir::new().unwrap();
+        let created = init(tmp.path(), &Config::default(), Some(Integration::MdBook)).unwrap();
+        assert_eq!(created.len(), 3);
+        assert!(tmp.path().join("book.toml").exists());
+        assert!(tmp.path().join("SUMMARY.md").exists());
+    }
+
+    #[test]
+    fn init_refuses_to_overwrite() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("conf.py"), "existing").unwrap();
+
+        let result = init(tmp.path(), &Config::default(), Some(Integration::Sphinx));
+        assert!(matches!(result, Err(InitError::AlreadyExists(path)) if path.ends_with("conf.py")));
+
+        // Nothing is written if anything would be overwritten
+        assert!(!tmp.path().join("config.toml").exists());
+    }
+}
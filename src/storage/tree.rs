@@ -4,15 +4,22 @@
 //! It is a simple in-memory representation of the requirements and their
 //! relationships.
 
-use std::{cmp::Ordering, collections::HashMap};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeSet, HashMap, HashSet, VecDeque},
+    fmt,
+};
 
 use tracing::instrument;
 use uuid::Uuid;
 
-use crate::Requirement;
+use crate::{
+    domain::{requirement::Parent, Hrid},
+    Requirement,
+};
 
 /// An in-memory representation of the set of requirements
-#[derive(Debug, Default, PartialEq)]
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct Tree {
     /// The requirements, stored contiguously.
     requirements: Vec<Requirement>,
@@ -20,53 +27,767 @@ pub struct Tree {
     /// An index from UUID to position in `requirements`.
     index: HashMap<Uuid, usize>,
 
+    /// An index from HRID to UUID.
+    hrids: HashMap<Hrid, Uuid>,
+
+    /// An index from UUID to the UUIDs of the requirement's children.
+    ///
+    /// Children are indexed by the UUIDs in their parent links, so a parent
+    /// need not be in the tree for its children to be indexed.
+    children: HashMap<Uuid, Vec<Uuid>>,
+
     /// A map from requirement kind to the next available index for that kind.
     next_indices: HashMap<String, usize>,
 }
 
 impl Tree {
+    /// Creates an empty tree with space for at least `capacity` requirements.
+    #[must_use]
     pub fn with_capacity(capacity: usize) -> Self {
         Self {
             requirements: Vec::with_capacity(capacity),
             index: HashMap::with_capacity(capacity),
+            hrids: HashMap::with_capacity(capacity),
+            children: HashMap::with_capacity(capacity),
             next_indices: HashMap::new(),
         }
     }
 
     /// Inserts a requirement into the tree.
-    /// Panics if a requirement with the same UUID already exists.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a requirement with the same UUID or HRID already exists.
     pub fn insert(&mut self, requirement: Requirement) {
         let uuid = requirement.uuid();
         assert!(
             !self.index.contains_key(&uuid),
             "Duplicate requirement UUID: {uuid}"
         );
+        let hrid = requirement.hrid();
+        assert!(
+            !self.hrids.contains_key(hrid),
+            "Duplicate requirement HRID: {hrid}"
+        );
         let index = self.requirements.len();
 
-        // Update the current index for the requirement's kind to the larger of its
-        // current value or the index of the incoming requirement.
-        let hrid = requirement.hrid();
-        let kind = hrid.kind();
+        self.reserve_index(hrid);
+
+        self.hrids.insert(hrid.clone(), uuid);
+        for (parent, _) in requirement.parents() {
+            self.children.entry(parent).or_default().push(uuid);
+        }
+        self.requirements.push(requirement);
+        self.index.insert(uuid, index);
+    }
+
+    /// Update the current index for the requirement's kind to the larger of its
+    /// current value or the index of the given HRID.
+    fn reserve_index(&mut self, hrid: &Hrid) {
         let suffix = hrid.id();
 
         self.next_indices
-            .entry(kind.to_string())
+            .entry(hrid.kind().to_string())
             .and_modify(|i| *i = (*i).max(suffix + 1))
             .or_insert(suffix + 1);
-
-        self.requirements.push(requirement);
-        self.index.insert(uuid, index);
     }
 
     /// Retrieves a requirement by UUID.
+    #[must_use]
     pub fn requirement(&self, uuid: Uuid) -> Option<&Requirement> {
         self.index
             .get(&uuid)
             .and_then(|&idx| self.requirements.get(idx))
     }
 
+    /// Returns an iterator over every requirement in the tree.
+    pub fn requirements(&self) -> impl Iterator<Item = &Requirement> {
+        self.requirements.iter()
+    }
+
+    fn requirement_mut(&mut self, uuid: Uuid) -> Option<&mut Requirement> {
+        self.index
+            .get(&uuid)
+            .and_then(|&idx| self.requirements.get_mut(idx))
+    }
+
+    /// Retrieves a requirement by HRID.
+    #[must_use]
+    pub fn find_by_hrid(&self, hrid: &Hrid) -> Option<&Requirement> {
+        self.hrids
+            .get(hrid)
+            .and_then(|&uuid| self.requirement(uuid))
+    }
+
+    /// Links a child requirement to a parent requirement.
+    ///
+    /// The link records the parent's current HRID and fingerprint. If the
+    /// requirements are already linked, the link's relation is kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either requirement is not in the tree, or if the
+    /// link would create a cycle.
+    pub fn link(&mut self, child: Uuid, parent: Uuid) -> Result<&Requirement, LinkError> {
+        self.link_as(child, parent, None)
+    }
+
+    /// Links a child requirement to a parent requirement with the given type
+    /// of relationship, such as 'verifies'.
+    ///
+    /// Linking requirements which are already linked replaces the existing
+    /// link. Its relation is replaced if `relation` is given, and kept
+    /// otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either requirement is not in the tree, or if the
+    /// link would create a cycle.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tree's index is inconsistent with its requirements.
+    pub fn link_as(
+        &mut self,
+        child: Uuid,
+        parent: Uuid,
+        relation: Option<String>,
+    ) -> Result<&Requirement, LinkError> {
+        let parent_requirement = self
+            .requirement(parent)
+            .ok_or(LinkError::NotFound(parent))?;
+        let relation = relation.or_else(|| {
+            self.requirement(child)?
+                .parents()
+                .find(|&(id, _)| id == parent)?
+                .1
+                .relation
+                .clone()
+        });
+        let parent_info = Parent {
+            hrid: parent_requirement.hrid().clone(),
+            fingerprint: parent_requirement.fingerprint(),
+            relation,
+        };
+
+        if !self.index.contains_key(&child) {
+            return Err(LinkError::NotFound(child));
+        }
+
+        if let Some(cycle) = self.cycle_through(child, parent) {
+            return Err(LinkError::Cycle(cycle));
+        }
+
+        let requirement = self
+            .requirement_mut(child)
+            .expect("child is known to exist");
+        if requirement.add_parent(parent, parent_info).is_none() {
+            self.children.entry(parent).or_default().push(child);
+        }
+
+        self.requirement(child).ok_or(LinkError::NotFound(child))
+    }
+
+    /// Removes the link between a child requirement and a parent.
+    ///
+    /// The parent need not be in the tree. Returns the removed link, or `None`
+    /// if the child is not in the tree or has no such parent.
+    pub fn unlink(&mut self, child: Uuid, parent: Uuid) -> Option<Parent> {
+        let removed = self.requirement_mut(child)?.remove_parent(parent)?;
+
+        if let Some(children) = self.children.get_mut(&parent) {
+            children.retain(|&uuid| uuid != child);
+            if children.is_empty() {
+                self.children.remove(&parent);
+            }
+        }
+
+        Some(removed)
+    }
+
+    /// Removes a requirement from the tree.
+    ///
+    /// Links from the requirement's children are left in place, and become
+    /// dangling. Use [`Tree::delete`] to handle the children.
+    pub fn remove(&mut self, uuid: Uuid) -> Option<Requirement> {
+        let index = self.index.remove(&uuid)?;
+        let requirement = self.requirements.swap_remove(index);
+
+        // The last requirement has moved into the vacated slot.
+        if let Some(moved) = self.requirements.get(index) {
+            self.index.insert(moved.uuid(), index);
+        }
+
+        if self.hrids.get(requirement.hrid()) == Some(&uuid) {
+            self.hrids.remove(requirement.hrid());
+        }
+        for (parent, _) in requirement.parents() {
+            if let Some(children) = self.children.get_mut(&parent) {
+                children.retain(|&child| child != uuid);
+                if children.is_empty() {
+                    self.children.remove(&parent);
+                }
+            }
+        }
+
+        Some(requirement)
+    }
+
+    /// Checks that a requirement can be deleted with the given policy, without
+    /// deleting it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the requirement is not in the tree, or if the policy
+    /// is [`DeletePolicy::Refuse`] and the requirement has children.
+    pub fn check_delete(&self, uuid: Uuid, policy: DeletePolicy) -> Result<(), DeleteError> {
+        let requirement = self.requirement(uuid).ok_or(DeleteError::NotFound(uuid))?;
+
+        if policy == DeletePolicy::Refuse && self.children(uuid).next().is_some() {
+            let mut hrids: Vec<Hrid> = self
+                .children(uuid)
+                .filter_map(|child| self.requirement(child))
+                .map(|child| child.hrid().clone())
+                .collect();
+            hrids.sort();
+            return Err(DeleteError::HasChildren {
+                hrid: requirement.hrid().clone(),
+                children: hrids,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Deletes a requirement, handling its children according to `policy`.
+    ///
+    /// Returns the deleted requirement, and the UUIDs of the children which
+    /// were modified.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the requirement is not in the tree, or if the policy
+    /// is [`DeletePolicy::Refuse`] and the requirement has children.
+    pub fn delete(
+        &mut self,
+        uuid: Uuid,
+        policy: DeletePolicy,
+    ) -> Result<(Requirement, Vec<Uuid>), DeleteError> {
+        self.check_delete(uuid, policy)?;
+        let requirement = self.requirement(uuid).ok_or(DeleteError::NotFound(uuid))?;
+        let children: Vec<Uuid> = self.children(uuid).collect();
+
+        // The children have never reviewed the deleted requirement's parents,
+        // so the new links are left suspect, with an empty fingerprint. They
+        // have no relation, since the children never had one with those
+        // parents.
+        let grandparents: Vec<(Uuid, Parent)> = match policy {
+            DeletePolicy::Reparent => requirement
+                .parents()
+                .filter_map(|(parent_id, _)| {
+                    let hrid = self.requirement(parent_id)?.hrid().clone();
+                    Some((
+                        parent_id,
+                        Parent {
+                            hrid,
+                            fingerprint: String::new(),
+                            relation: None,
+                        },
+                    ))
+                })
+                .collect(),
+            DeletePolicy::Refuse | DeletePolicy::Detach => Vec::new(),
+        };
+
+        for &child in &children {
+            self.unlink(child, uuid);
+            for (parent_id, parent) in &grandparents {
+                let Some(requirement) = self.requirement_mut(child) else {
+                    continue;
+                };
+                if requirement.parents().all(|(id, _)| id != *parent_id) {
+                    requirement.add_parent(*parent_id, parent.clone());
+                    self.children.entry(*parent_id).or_default().push(child);
+                }
+            }
+        }
+
+        let requirement = self.remove(uuid).ok_or(DeleteError::NotFound(uuid))?;
+
+        Ok((requirement, children))
+    }
+
+    /// Finds the cycle which a new link from `child` to `parent` would close.
+    ///
+    /// The new link closes a cycle if the child is already an ancestor of the
+    /// parent.
+    fn cycle_through(&self, child: Uuid, parent: Uuid) -> Option<Cycle> {
+        let path = self.path_to_ancestor(parent, child)?;
+        let hrids = std::iter::once(child)
+            .chain(path)
+            .filter_map(|uuid| self.requirement(uuid))
+            .map(|requirement| requirement.hrid().clone())
+            .collect();
+        Some(Cycle(hrids))
+    }
+
+    /// Finds a chain of parent links leading from `from` to `to`.
+    ///
+    /// The returned path starts at `from` and excludes `to`. A requirement is
+    /// considered its own ancestor, in which case the path is empty.
+    fn path_to_ancestor(&self, from: Uuid, to: Uuid) -> Option<Vec<Uuid>> {
+        let mut predecessors: HashMap<Uuid, Uuid> = HashMap::new();
+        let mut stack = vec![from];
+
+        while let Some(current) = stack.pop() {
+            if current == to {
+                let mut path = Vec::new();
+                let mut node = current;
+                while let Some(&previous) = predecessors.get(&node) {
+                    path.push(previous);
+                    node = previous;
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            let Some(requirement) = self.requirement(current) else {
+                continue;
+            };
+
+            for (parent, _) in requirement.parents() {
+                if parent != from && !predecessors.contains_key(&parent) {
+                    predecessors.insert(parent, current);
+                    stack.push(parent);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Finds the cycles in the graph of parent links.
+    ///
+    /// Every link that closes a cycle is reported once, together with the
+    /// chain of links that it closes. Links to requirements which are not in
+    /// the tree are ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the depth-first search loses track of a requirement it is
+    /// still visiting, which would be a bug.
+    #[must_use]
+    pub fn find_cycles(&self) -> Vec<Cycle> {
+        #[derive(Clone, Copy, PartialEq, Eq)]
+        enum Visit {
+            InProgress,
+            Done,
+        }
+
+        let mut visits: HashMap<Uuid, Visit> = HashMap::with_capacity(self.requirements.len());
+        let mut cycles = Vec::new();
+
+        for root in &self.requirements {
+            if visits.contains_key(&root.uuid()) {
+                continue;
+            }
+
+            visits.insert(root.uuid(), Visit::InProgress);
+            let mut stack = vec![(root.uuid(), self.parent_ids(root))];
+
+            while let Some((current, pending)) = stack.last_mut() {
+                let current = *current;
+                let Some(parent) = pending.pop() else {
+                    visits.insert(current, Visit::Done);
+                    stack.pop();
+                    continue;
+                };
+
+                match visits.get(&parent) {
+                    None => {
+                        let Some(requirement) = self.requirement(parent) else {
+                            continue;
+                        };
+                        visits.insert(parent, Visit::InProgress);
+                        stack.push((parent, self.parent_ids(requirement)));
+                    }
+                    Some(Visit::InProgress) => {
+                        let start = stack
+                            .iter()
+                            .position(|&(uuid, _)| uuid == parent)
+                            .expect("in-progress requirements are on the stack");
+                        let hrids = stack[start..]
+                            .iter()
+                            .filter_map(|&(uuid, _)| self.requirement(uuid))
+                            .map(|requirement| requirement.hrid().clone())
+                            .collect();
+                        cycles.push(Cycle(hrids));
+                    }
+                    Some(Visit::Done) => {}
+                }
+            }
+        }
+
+        cycles
+    }
+
+    /// Whether the link from `child` to `parent` is suspect.
+    ///
+    /// A link is suspect if the fingerprint stored in the child no longer
+    /// matches the parent's current fingerprint: the parent has changed since
+    /// the link was last reviewed, so the child may need to be reviewed too.
+    ///
+    /// Returns `None` if either requirement is not in the tree, or if `child`
+    /// doesn't link to `parent`.
+    #[must_use]
+    pub fn is_suspect(&self, child: Uuid, parent: Uuid) -> Option<bool> {
+        let link = self.requirement(child)?.parent(parent)?;
+        let parent = self.requirement(parent)?;
+        Some(parent.fingerprint() != link.fingerprint)
+    }
+
+    /// Finds the parent links which are suspect.
+    ///
+    /// See [`Tree::is_suspect`]. Links to requirements which are not in the
+    /// tree are ignored.
+    ///
+    /// The links are sorted by child HRID, then parent HRID.
+    #[must_use]
+    pub fn suspect_links(&self) -> Vec<SuspectLink> {
+        let mut suspect_links = Vec::new();
+
+        for child in &self.requirements {
+            for (parent_id, parent) in child.parents() {
+                if self.is_suspect(child.uuid(), parent_id) != Some(true) {
+                    continue;
+                }
+                let Some(parent_requirement) = self.requirement(parent_id) else {
+                    continue;
+                };
+
+                suspect_links.push(SuspectLink {
+                    child: child.uuid(),
+                    child_hrid: child.hrid().clone(),
+                    parent: parent_id,
+                    parent_hrid: parent_requirement.hrid().clone(),
+                    stored_fingerprint: parent.fingerprint.clone(),
+                    current_fingerprint: parent_requirement.fingerprint(),
+                });
+            }
+        }
+
+        suspect_links
+            .sort_by(|a, b| (&a.child_hrid, &a.parent_hrid).cmp(&(&b.child_hrid, &b.parent_hrid)));
+        suspect_links
+    }
+
+    /// Marks a requirement's parent links as reviewed.
+    ///
+    /// The stored fingerprints are replaced with the parents' current
+    /// fingerprints. If `parent` is given, only the link to that parent is
+    /// reviewed. Links to requirements which are not in the tree are left
+    /// unchanged.
+    ///
+    /// Returns the UUIDs of the parents whose stored fingerprints changed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tree's index is inconsistent with its requirements.
+    pub fn review(&mut self, child: Uuid, parent: Option<Uuid>) -> Vec<Uuid> {
+        let Some(requirement) = self.requirement(child) else {
+            return Vec::new();
+        };
+
+        let fingerprints: HashMap<Uuid, String> = requirement
+            .parents()
+            .map(|(parent_id, _)| parent_id)
+            .filter(|&parent_id| parent.is_none_or(|parent| parent == parent_id))
+            .filter_map(|parent_id| {
+                self.requirement(parent_id)
+                    .map(|parent| (parent_id, parent.fingerprint()))
+            })
+            .collect();
+
+        let requirement = self
+            .requirement_mut(child)
+            .expect("child is known to exist");
+
+        requirement
+            .parents_mut()
+            .filter_map(|(parent_id, parent)| {
+                let fingerprint = fingerprints.get(&parent_id)?;
+                if parent.fingerprint == *fingerprint {
+                    None
+                } else {
+                    parent.fingerprint.clone_from(fingerprint);
+                    Some(parent_id)
+                }
+            })
+            .collect()
+    }
+
+    /// Changes the HRID of a requirement, updating every reference to it.
+    ///
+    /// The parent HRIDs recorded in the requirement's children are updated,
+    /// and whole-word references to the old HRID in the body of any
+    /// requirement are rewritten. Rewriting a body changes that requirement's
+    /// fingerprint, so links to it which were up to date are refreshed rather
+    /// than becoming suspect.
+    ///
+    /// Returns the UUIDs of every requirement which was modified, including the
+    /// renamed requirement.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the requirement is not in the tree, or if another
+    /// requirement already has the new HRID.
+    pub fn rename(&mut self, uuid: Uuid, hrid: Hrid) -> Result<Vec<Uuid>, RenameError> {
+        if let Some(&existing) = self.hrids.get(&hrid) {
+            if existing != uuid {
+                return Err(RenameError::Taken(hrid));
+            }
+        }
+
+        let requirement = self
+            .requirement_mut(uuid)
+            .ok_or(RenameError::NotFound(uuid))?;
+        let old = requirement.hrid().clone();
+        requirement.set_hrid(hrid.clone());
+
+        self.hrids.remove(&old);
+        self.hrids.insert(hrid.clone(), uuid);
+        self.reserve_index(&hrid);
+
+        let (old, new) = (old.to_string(), hrid.to_string());
+        let mut modified = HashSet::from([uuid]);
+
+        // Rewrite references in the bodies, remembering the old and new
+        // fingerprints of each rewritten requirement.
+        let mut fingerprints: HashMap<Uuid, (String, String)> = HashMap::new();
+        for requirement in &mut self.requirements {
+            let Some(content) = replace_word(requirement.content(), &old, &new) else {
+                continue;
+            };
+            let before = requirement.fingerprint();
+            requirement.set_content(content);
+            fingerprints.insert(requirement.uuid(), (before, requirement.fingerprint()));
+            modified.insert(requirement.uuid());
+        }
+
+        for requirement in &mut self.requirements {
+            let child = requirement.uuid();
+            for (parent_id, parent) in requirement.parents_mut() {
+                if parent_id == uuid {
+                    parent.hrid = hrid.clone();
+                    modified.insert(child);
+                }
+                if let Some((before, after)) = fingerprints.get(&parent_id) {
+                    if parent.fingerprint == *before {
+                        parent.fingerprint.clone_from(after);
+                        modified.insert(child);
+                    }
+                }
+            }
+        }
+
+        Ok(modified.into_iter().collect())
+    }
+
+    /// Pads the ID of every HRID to the given number of digits.
+    ///
+    /// This is equivalent to renaming every requirement, so references in the
+    /// bodies of requirements and the parent HRIDs recorded in children are
+    /// updated too.
+    ///
+    /// Returns the UUIDs of every requirement which was modified.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a requirement cannot be renamed, in which case some
+    /// requirements may already have been renamed.
+    pub fn set_digits(&mut self, digits: usize) -> Result<Vec<Uuid>, RenameError> {
+        let renames: Vec<(Uuid, Hrid)> = self
+            .requirements
+            .iter()
+            .filter(|requirement| requirement.hrid().digits() != digits)
+            .map(|requirement| {
+                let hrid = requirement.hrid().clone().with_digits(digits);
+                (requirement.uuid(), hrid)
+            })
+            .collect();
+
+        let mut modified = HashSet::new();
+        for (uuid, hrid) in renames {
+            modified.extend(self.rename(uuid, hrid)?);
+        }
+
+        Ok(modified.into_iter().collect())
+    }
+
+    /// Replaces the tags on a requirement.
+    ///
+    /// Tags contribute to the fingerprint, so changing them makes the links
+    /// from the requirement's children suspect. Returns the UUIDs of the
+    /// children whose links were up to date and have become suspect, or `None`
+    /// if the requirement is not in the tree.
+    pub fn set_tags(&mut self, uuid: Uuid, tags: BTreeSet<String>) -> Option<Vec<Uuid>> {
+        let requirement = self.requirement_mut(uuid)?;
+        let before = requirement.fingerprint();
+        requirement.set_tags(tags);
+
+        Some(self.newly_suspect_children(uuid, &before))
+    }
+
+    /// Replaces a requirement with an updated version of itself.
+    ///
+    /// The requirement with the same UUID is replaced, and the children index
+    /// is updated to reflect any change to its parents. Returns the UUIDs of
+    /// the children whose links were up to date and have become suspect.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if [`Tree::check_replace`] does.
+    pub fn replace(&mut self, requirement: Requirement) -> Result<Vec<Uuid>, ReplaceError> {
+        self.check_replace(&requirement)?;
+        let uuid = requirement.uuid();
+        let existing = self
+            .requirement_mut(uuid)
+            .ok_or(ReplaceError::NotFound(uuid))?;
+
+        let before = existing.fingerprint();
+        let old = std::mem::replace(existing, requirement);
+
+        for (parent, _) in old.parents() {
+            if let Some(children) = self.children.get_mut(&parent) {
+                children.retain(|&child| child != uuid);
+                if children.is_empty() {
+                    self.children.remove(&parent);
+                }
+            }
+        }
+        let parents: Vec<Uuid> = self
+            .requirement(uuid)
+            .into_iter()
+            .flat_map(Requirement::parents)
+            .map(|(parent, _)| parent)
+            .collect();
+        for parent in parents {
+            self.children.entry(parent).or_default().push(uuid);
+        }
+
+        Ok(self.newly_suspect_children(uuid, &before))
+    }
+
+    /// Checks that a requirement could replace the one with the same UUID,
+    /// without replacing it.
+    ///
+    /// Only the parents which the existing requirement doesn't already have are
+    /// checked, so links which are already dangling are allowed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no requirement with the same UUID is in the tree, if
+    /// the HRID has changed, or if a new parent is not in the tree or would
+    /// create a cycle. Use [`Tree::rename`] to change the HRID.
+    pub fn check_replace(&self, requirement: &Requirement) -> Result<(), ReplaceError> {
+        let uuid = requirement.uuid();
+        let existing = self.requirement(uuid).ok_or(ReplaceError::NotFound(uuid))?;
+
+        if existing.hrid() != requirement.hrid() {
+            return Err(ReplaceError::HridChanged {
+                from: existing.hrid().clone(),
+                to: requirement.hrid().clone(),
+            });
+        }
+
+        let new_parents = requirement
+            .parents()
+            .filter(|&(parent, _)| existing.parents().all(|(id, _)| id != parent));
+        for (parent, link) in new_parents {
+            if self.requirement(parent).is_none() {
+                return Err(ReplaceError::ParentNotFound {
+                    uuid: parent,
+                    hrid: link.hrid.clone(),
+                });
+            }
+            if let Some(cycle) = self.cycle_through(uuid, parent) {
+                return Err(ReplaceError::Cycle(cycle));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Finds the children whose links to a requirement were up to date with
+    /// the `before` fingerprint, but are not up to date with the current one.
+    fn newly_suspect_children(&self, uuid: Uuid, before: &str) -> Vec<Uuid> {
+        let Some(after) = self.requirement(uuid).map(Requirement::fingerprint) else {
+            return Vec::new();
+        };
+        if before == after {
+            return Vec::new();
+        }
+
+        self.children(uuid)
+            .filter(|&child| {
+                self.requirement(child).is_some_and(|child| {
+                    child.parents().any(|(parent_id, parent)| {
+                        parent_id == uuid && parent.fingerprint == before
+                    })
+                })
+            })
+            .collect()
+    }
+
+    /// Returns an iterator over the UUIDs of a requirement's children.
+    ///
+    /// The children are the requirements which link to the given requirement
+    /// as a parent.
+    pub fn children(&self, uuid: Uuid) -> impl Iterator<Item = Uuid> + '_ {
+        self.children.get(&uuid).into_iter().flatten().copied()
+    }
+
+    /// Returns an iterator over the UUIDs of a requirement's parents.
+    ///
+    /// Parent links to requirements which are not in the tree are skipped.
+    pub fn parents(&self, uuid: Uuid) -> impl Iterator<Item = Uuid> + '_ {
+        self.requirement(uuid)
+            .into_iter()
+            .flat_map(Requirement::parents)
+            .map(|(parent, _)| parent)
+            .filter(|parent| self.index.contains_key(parent))
+    }
+
+    /// Returns an iterator over the UUIDs of all of a requirement's
+    /// descendants, in breadth-first order.
+    ///
+    /// Each descendant is visited once, even if it can be reached along more
+    /// than one path.
+    pub fn descendants(&self, uuid: Uuid) -> impl Iterator<Item = Uuid> + '_ {
+        Walk::new(uuid, move |uuid| self.children(uuid).collect())
+    }
+
+    /// Returns an iterator over the UUIDs of all of a requirement's ancestors,
+    /// in breadth-first order.
+    ///
+    /// Each ancestor is visited once, even if it can be reached along more
+    /// than one path.
+    pub fn ancestors(&self, uuid: Uuid) -> impl Iterator<Item = Uuid> + '_ {
+        Walk::new(uuid, move |uuid| self.parents(uuid).collect())
+    }
+
+    fn parent_ids(&self, requirement: &Requirement) -> Vec<Uuid> {
+        requirement
+            .parents()
+            .map(|(uuid, _)| uuid)
+            .filter(|uuid| self.index.contains_key(uuid))
+            .collect()
+    }
+
     /// Read all the requirements and update any incorrect parent HRIDs.
     /// Returns an iterator of UUIDs whose parents were updated.
+    ///
+    /// Broken links are left untouched. See [`Tree::broken_links`].
     #[instrument(skip(self))]
     pub fn update_hrids(&mut self) -> impl Iterator<Item = Uuid> + '_ {
         (0..self.requirements.len()).filter_map(|i| {
@@ -77,20 +798,19 @@ impl Tree {
             let updated: Vec<bool> = req
                 .parents_mut()
                 .map(|(parent_id, parent)| {
-                    let &parent_idx = self
-                        .index
-                        .get(&parent_id)
-                        .unwrap_or_else(|| panic!("Parent requirement {parent_id} not found!"));
+                    let Some(&parent_idx) = self.index.get(&parent_id) else {
+                        return false;
+                    };
 
                     let actual_hrid = match parent_idx.cmp(&i) {
                         Ordering::Less => left[parent_idx].hrid(),
                         Ordering::Greater => right[parent_idx - i - 1].hrid(),
-                        Ordering::Equal => {
-                            unreachable!("Requirement {parent_id} is its own parent!")
-                        }
+                        Ordering::Equal => return false,
                     };
 
-                    if parent.hrid == *actual_hrid {
+                    // HRIDs which differ only in their padding are equal, but
+                    // the padding is still corrected.
+                    if parent.hrid == *actual_hrid && parent.hrid.digits() == actual_hrid.digits() {
                         false
                     } else {
                         parent.hrid = actual_hrid.clone();
@@ -108,21 +828,338 @@ impl Tree {
         })
     }
 
+    /// Finds parent links which cannot be followed.
+    ///
+    /// A link is broken if the parent is not in the tree, or if a requirement
+    /// is its own parent. These are sorted by the HRIDs of the child and the
+    /// (last known) parent.
+    #[must_use]
+    pub fn broken_links(&self) -> Vec<BrokenLink> {
+        let mut broken_links: Vec<BrokenLink> = self
+            .requirements
+            .iter()
+            .flat_map(|child| {
+                child.parents().filter_map(|(parent_id, parent)| {
+                    let kind = if parent_id == child.uuid() {
+                        BrokenLinkKind::SelfParent
+                    } else if self.index.contains_key(&parent_id) {
+                        return None;
+                    } else {
+                        BrokenLinkKind::Dangling
+                    };
+
+                    Some(BrokenLink {
+                        child: child.uuid(),
+                        child_hrid: child.hrid().clone(),
+                        parent: parent_id,
+                        parent_hrid: parent.hrid.clone(),
+                        kind,
+                    })
+                })
+            })
+            .collect();
+
+        broken_links
+            .sort_by(|a, b| (&a.child_hrid, &a.parent_hrid).cmp(&(&b.child_hrid, &b.parent_hrid)));
+        broken_links
+    }
+
+    /// Removes every broken link from the tree.
+    ///
+    /// Returns the links which were removed.
+    pub fn prune_broken_links(&mut self) -> Vec<BrokenLink> {
+        let broken_links = self.broken_links();
+
+        for link in &broken_links {
+            self.unlink(link.child, link.parent);
+        }
+
+        broken_links
+    }
+
     /// Returns the next available index for a requirement of the given kind.
     ///
     /// This is one greater than the highest index currently used for that kind.
     /// No attempt is made to 'recycle' indices if there are gaps in the
     /// sequence.
+    #[must_use]
     pub fn next_index(&self, kind: &str) -> usize {
         self.next_indices.get(kind).copied().unwrap_or(1)
     }
 }
 
+/// A breadth-first walk over the graph of requirements.
+struct Walk<F> {
+    neighbours: F,
+    queue: VecDeque<Uuid>,
+    visited: HashSet<Uuid>,
+}
+
+impl<F> Walk<F>
+where
+    F: Fn(Uuid) -> Vec<Uuid>,
+{
+    fn new(start: Uuid, neighbours: F) -> Self {
+        let mut walk = Self {
+            neighbours,
+            queue: VecDeque::new(),
+            visited: HashSet::from([start]),
+        };
+        walk.enqueue_neighbours(start);
+        walk
+    }
+
+    fn enqueue_neighbours(&mut self, uuid: Uuid) {
+        for neighbour in (self.neighbours)(uuid) {
+            if self.visited.insert(neighbour) {
+                self.queue.push_back(neighbour);
+            }
+        }
+    }
+}
+
+impl<F> Iterator for Walk<F>
+where
+    F: Fn(Uuid) -> Vec<Uuid>,
+{
+    type Item = Uuid;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self.queue.pop_front()?;
+        self.enqueue_neighbours(next);
+        Some(next)
+    }
+}
+
+/// A cycle of parent links.
+///
+/// Each requirement in the cycle is a child of the one that follows it, and the
+/// last requirement is a child of the first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle(Vec<Hrid>);
+
+impl Cycle {
+    /// The HRIDs of the requirements in the cycle, in order.
+    #[must_use]
+    pub fn hrids(&self) -> &[Hrid] {
+        &self.0
+    }
+}
+
+impl fmt::Display for Cycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for hrid in &self.0 {
+            write!(f, "{hrid} -> ")?;
+        }
+        self.0.first().map_or(Ok(()), |first| write!(f, "{first}"))
+    }
+}
+
+/// A parent link whose parent has changed since the link was last reviewed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuspectLink {
+    /// The UUID of the child requirement.
+    pub child: Uuid,
+
+    /// The HRID of the child requirement.
+    pub child_hrid: Hrid,
+
+    /// The UUID of the parent requirement.
+    pub parent: Uuid,
+
+    /// The current HRID of the parent requirement.
+    pub parent_hrid: Hrid,
+
+    /// The parent fingerprint stored in the child when the link was last
+    /// reviewed.
+    pub stored_fingerprint: String,
+
+    /// The parent's current fingerprint.
+    pub current_fingerprint: String,
+}
+
+/// Replaces whole-word occurrences of `from` in `text` with `to`.
+///
+/// A match must not be preceded or followed by a character which could be part
+/// of an HRID, so that (for example) `USR-001` does not match within
+/// `USR-0010` or `NS-USR-001`. Returns `None` if there are no matches.
+fn replace_word(text: &str, from: &str, to: &str) -> Option<String> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '-' || c == '_';
+
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    let mut replaced = false;
+
+    while let Some(position) = rest.find(from) {
+        let (before, after) = (&rest[..position], &rest[position + from.len()..]);
+        let preceded = before
+            .chars()
+            .next_back()
+            .or_else(|| result.chars().next_back())
+            .is_some_and(is_word);
+        let followed = after.chars().next().is_some_and(is_word);
+
+        result.push_str(before);
+        if preceded || followed {
+            result.push_str(from);
+        } else {
+            result.push_str(to);
+            replaced = true;
+        }
+        rest = after;
+    }
+    result.push_str(rest);
+
+    replaced.then_some(result)
+}
+
+/// What to do with the children of a deleted requirement.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DeletePolicy {
+    /// Refuse to delete a requirement which has children.
+    #[default]
+    Refuse,
+
+    /// Remove the link from each child.
+    Detach,
+
+    /// Link each child to the deleted requirement's parents instead.
+    ///
+    /// The new links are suspect until they are reviewed.
+    Reparent,
+}
+
+/// Errors that can occur when deleting a requirement from a [`Tree`].
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum DeleteError {
+    /// No requirement with the given UUID is in the tree.
+    #[error("requirement {0} not found")]
+    NotFound(Uuid),
+
+    /// The requirement has children, and the policy is to refuse.
+    #[error("{hrid} has children: {}", .children.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))]
+    HasChildren {
+        /// The HRID of the requirement.
+        hrid: Hrid,
+
+        /// The HRIDs of its children.
+        children: Vec<Hrid>,
+    },
+}
+
+/// Errors that can occur when replacing a requirement in a [`Tree`].
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum ReplaceError {
+    /// No requirement with the given UUID is in the tree.
+    #[error("requirement {0} not found")]
+    NotFound(Uuid),
+
+    /// The replacement has a different HRID.
+    #[error("the HRID cannot be changed from {from} to {to}")]
+    HridChanged {
+        /// The existing HRID.
+        from: Hrid,
+
+        /// The HRID of the replacement.
+        to: Hrid,
+    },
+
+    /// A new parent is not in the tree.
+    #[error("parent {hrid} ({uuid}) not found")]
+    ParentNotFound {
+        /// The UUID of the parent.
+        uuid: Uuid,
+
+        /// The HRID recorded in the link.
+        hrid: Hrid,
+    },
+
+    /// A new parent would create a cycle.
+    #[error("parent would create a cycle: {0}")]
+    Cycle(Cycle),
+}
+
+/// Errors that can occur when renaming a requirement in a [`Tree`].
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum RenameError {
+    /// No requirement with the given UUID is in the tree.
+    #[error("requirement {0} not found")]
+    NotFound(Uuid),
+
+    /// Another requirement already has the new HRID.
+    #[error("requirement {0} already exists")]
+    Taken(Hrid),
+}
+
+/// A parent link which cannot be followed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrokenLink {
+    /// The UUID of the child requirement.
+    pub child: Uuid,
+
+    /// The HRID of the child requirement.
+    pub child_hrid: Hrid,
+
+    /// The UUID the child links to.
+    pub parent: Uuid,
+
+    /// The parent HRID recorded in the child when the link was last updated.
+    pub parent_hrid: Hrid,
+
+    /// Why the link is broken.
+    pub kind: BrokenLinkKind,
+}
+
+/// The ways in which a parent link can be broken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BrokenLinkKind {
+    /// The parent is not in the tree.
+    Dangling,
+
+    /// The requirement links to itself.
+    SelfParent,
+}
+
+impl fmt::Display for BrokenLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            BrokenLinkKind::Dangling => write!(
+                f,
+                "{} links to missing parent {} ({})",
+                self.child_hrid, self.parent_hrid, self.parent
+            ),
+            BrokenLinkKind::SelfParent => write!(f, "{} is its own parent", self.child_hrid),
+        }
+    }
+}
+
+/// Errors that can occur when linking two requirements in a [`Tree`].
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum LinkError {
+    /// No requirement with the given UUID is in the tree.
+    #[error("requirement {0} not found")]
+    NotFound(Uuid),
+
+    /// The link would create a cycle.
+    #[error("link would create a cycle: {0}")]
+    Cycle(Cycle),
+}
+
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeSet;
+
     use uuid::Uuid;
 
-    use crate::{domain::Hrid, storage::Tree, Requirement};
+    use super::{
+        replace_word, BrokenLinkKind, Cycle, DeleteError, DeletePolicy, LinkError, RenameError,
+        ReplaceError, SuspectLink,
+    };
+    use crate::{
+        domain::{requirement::Parent, Hrid},
+        storage::Tree,
+        Requirement,
+    };
 
     fn make_requirement(uuid: Uuid, hrid: Hrid, parents: Vec<(Uuid, Hrid)>) -> Requirement {
         let mut req = Requirement::new_with_uuid(hrid, String::new(), uuid);
@@ -132,6 +1169,7 @@ mod tests {
                 crate::domain::requirement::Parent {
                     hrid: parent_hrid,
                     fingerprint: String::new(),
+                    relation: None,
                 },
             );
         }
@@ -162,6 +1200,16 @@ mod tests {
         tree.insert(req2); // should panic
     }
 
+    #[test]
+    #[should_panic(expected = "Duplicate requirement HRID")]
+    fn test_insert_duplicate_hrid_panics() {
+        let mut tree = Tree::default();
+        let req1 = make_requirement(Uuid::new_v4(), Hrid::try_from("R-001").unwrap(), vec![]);
+        let req2 = make_requirement(Uuid::new_v4(), Hrid::try_from("R-1").unwrap(), vec![]);
+        tree.insert(req1);
+        tree.insert(req2); // should panic
+    }
+
     #[test]
     fn update_hrids_corrects_parent_hrids() {
         let mut tree = Tree::default();
@@ -207,33 +1255,689 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Parent requirement")]
-    fn test_update_hrids_missing_parent_panics() {
+    fn unlink_removes_parent_and_child_index() {
+        let mut tree = Tree::default();
+        let parent = Uuid::new_v4();
+        let child = Uuid::new_v4();
+        let missing = Uuid::new_v4();
+
+        tree.insert(make_requirement(
+            parent,
+            Hrid::try_from("P-001").unwrap(),
+            vec![],
+        ));
+        tree.insert(make_requirement(
+            child,
+            Hrid::try_from("C-001").unwrap(),
+            vec![
+                (parent, Hrid::try_from("P-001").unwrap()),
+                (missing, Hrid::try_from("GONE-001").unwrap()),
+            ],
+        ));
+
+        let removed = tree.unlink(child, parent).unwrap();
+        assert_eq!(removed.hrid, Hrid::try_from("P-001").unwrap());
+        assert_eq!(tree.children(parent).count(), 0);
+
+        assert!(tree.unlink(child, missing).is_some());
+        assert_eq!(tree.requirement(child).unwrap().parents().count(), 0);
+
+        assert!(tree.unlink(child, parent).is_none());
+    }
+
+    #[test]
+    fn replace_word_respects_boundaries() {
+        assert_eq!(
+            replace_word(
+                "see USR-001, not USR-0010 or NS-USR-001",
+                "USR-001",
+                "SYS-002"
+            ),
+            Some("see SYS-002, not USR-0010 or NS-USR-001".to_string())
+        );
+        assert_eq!(
+            replace_word("USR-001USR-001 (USR-001)", "USR-001", "X-001"),
+            Some("USR-001USR-001 (X-001)".to_string())
+        );
+        assert_eq!(replace_word("nothing here", "USR-001", "X-001"), None);
+    }
+
+    #[test]
+    fn rename_updates_references() {
+        let mut tree = Tree::default();
+        let parent = Uuid::new_v4();
+        let child = Uuid::new_v4();
+        let grandchild = Uuid::new_v4();
+
+        let mut parent_requirement =
+            make_requirement(parent, Hrid::try_from("P-001").unwrap(), vec![]);
+        parent_requirement.set_content("This is P-001.".to_string());
+        tree.insert(parent_requirement);
+
+        let mut child_requirement =
+            make_requirement(child, Hrid::try_from("C-001").unwrap(), vec![]);
+        child_requirement.set_content("Refines P-001.".to_string());
+        tree.insert(child_requirement);
+        tree.link(child, parent).unwrap();
+
+        tree.insert(make_requirement(
+            grandchild,
+            Hrid::try_from("G-001").unwrap(),
+            vec![],
+        ));
+        tree.link(grandchild, child).unwrap();
+
+        tree.insert(make_requirement(
+            Uuid::new_v4(),
+            Hrid::try_from("Q-001").unwrap(),
+            vec![],
+        ));
+        assert_eq!(
+            tree.rename(parent, Hrid::try_from("Q-001").unwrap()),
+            Err(RenameError::Taken(Hrid::try_from("Q-001").unwrap()))
+        );
+
+        let new = Hrid::try_from("NS-Q-002").unwrap();
+        let mut modified = tree.rename(parent, new.clone()).unwrap();
+        modified.sort();
+        let mut expected = vec![parent, child, grandchild];
+        expected.sort();
+        assert_eq!(modified, expected);
+
+        assert_eq!(tree.find_by_hrid(&new).unwrap().uuid(), parent);
+        assert!(tree
+            .find_by_hrid(&Hrid::try_from("P-001").unwrap())
+            .is_none());
+        assert_eq!(
+            tree.requirement(parent).unwrap().content(),
+            "This is NS-Q-002."
+        );
+        assert_eq!(
+            tree.requirement(child).unwrap().content(),
+            "Refines NS-Q-002."
+        );
+
+        let (_, link) = tree.requirement(child).unwrap().parents().next().unwrap();
+        assert_eq!(link.hrid, new);
+
+        // The rewritten bodies must not leave any links suspect.
+        assert!(tree.suspect_links().is_empty());
+    }
+
+    #[test]
+    fn set_digits_pads_every_reference() {
+        let mut tree = Tree::default();
+        let parent = Uuid::new_v4();
+        let child = Uuid::new_v4();
+
+        tree.insert(make_requirement(
+            parent,
+            Hrid::try_from("P-001").unwrap(),
+            vec![],
+        ));
+        let mut child_requirement =
+            make_requirement(child, Hrid::try_from("C-001").unwrap(), vec![]);
+        child_requirement.set_content("Refines P-001.".to_string());
+        tree.insert(child_requirement);
+        tree.link(child, parent).unwrap();
+
+        let mut modified = tree.set_digits(4).unwrap();
+        modified.sort();
+        let mut expected = vec![parent, child];
+        expected.sort();
+        assert_eq!(modified, expected);
+
+        let child_requirement = tree.requirement(child).unwrap();
+        assert_eq!(child_requirement.hrid().to_string(), "C-0001");
+        assert_eq!(child_requirement.content(), "Refines P-0001.");
+        let (_, link) = child_requirement.parents().next().unwrap();
+        assert_eq!(link.hrid.to_string(), "P-0001");
+        assert!(tree.suspect_links().is_empty());
+
+        // Nothing changes if the HRIDs are already padded
+        assert!(tree.set_digits(4).unwrap().is_empty());
+    }
+
+    #[test]
+    fn remove_keeps_indices_consistent() {
+        let mut tree = Tree::default();
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+        let c = Uuid::new_v4();
+
+        tree.insert(make_requirement(
+            a,
+            Hrid::try_from("A-001").unwrap(),
+            vec![],
+        ));
+        tree.insert(make_requirement(
+            b,
+            Hrid::try_from("B-001").unwrap(),
+            vec![(a, Hrid::try_from("A-001").unwrap())],
+        ));
+        tree.insert(make_requirement(
+            c,
+            Hrid::try_from("C-001").unwrap(),
+            vec![(a, Hrid::try_from("A-001").unwrap())],
+        ));
+
+        let removed = tree.remove(b).unwrap();
+        assert_eq!(removed.uuid(), b);
+
+        assert!(tree.requirement(b).is_none());
+        assert!(tree
+            .find_by_hrid(&Hrid::try_from("B-001").unwrap())
+            .is_none());
+        assert_eq!(tree.requirement(c).unwrap().uuid(), c);
+        assert_eq!(tree.children(a).collect::<Vec<_>>(), vec![c]);
+        assert_eq!(tree.next_index("B"), 2);
+    }
+
+    #[test]
+    fn delete_applies_policy_to_children() {
+        let mut tree = Tree::default();
+        let grandparent = Uuid::new_v4();
+        let parent = Uuid::new_v4();
+        let child = Uuid::new_v4();
+
+        tree.insert(make_requirement(
+            grandparent,
+            Hrid::try_from("G-001").unwrap(),
+            vec![],
+        ));
+        tree.insert(make_requirement(
+            parent,
+            Hrid::try_from("P-001").unwrap(),
+            vec![],
+        ));
+        tree.insert(make_requirement(
+            child,
+            Hrid::try_from("C-001").unwrap(),
+            vec![],
+        ));
+        tree.link(parent, grandparent).unwrap();
+        tree.link(child, parent).unwrap();
+
+        assert_eq!(
+            tree.delete(parent, DeletePolicy::Refuse),
+            Err(DeleteError::HasChildren {
+                hrid: Hrid::try_from("P-001").unwrap(),
+                children: vec![Hrid::try_from("C-001").unwrap()],
+            })
+        );
+
+        let mut detached = tree.clone();
+        let (_, modified) = detached.delete(parent, DeletePolicy::Detach).unwrap();
+        assert_eq!(modified, vec![child]);
+        assert_eq!(detached.requirement(child).unwrap().parents().count(), 0);
+
+        let (removed, modified) = tree.delete(parent, DeletePolicy::Reparent).unwrap();
+        assert_eq!(removed.uuid(), parent);
+        assert_eq!(modified, vec![child]);
+        assert_eq!(tree.parents(child).collect::<Vec<_>>(), vec![grandparent]);
+        assert_eq!(tree.children(grandparent).collect::<Vec<_>>(), vec![child]);
+
+        // The child has never reviewed its new parent
+        let suspect = tree.suspect_links();
+        assert_eq!(suspect.len(), 1);
+        assert_eq!((suspect[0].child, suspect[0].parent), (child, grandparent));
+        assert_eq!(suspect[0].stored_fingerprint, "");
+        let link = tree
+            .requirement(child)
+            .unwrap()
+            .parent(grandparent)
+            .unwrap();
+        assert_eq!(link.relation, None);
+    }
+
+    #[test]
+    fn set_tags_reports_newly_suspect_children() {
+        let mut tree = Tree::default();
+        let parent = Uuid::new_v4();
+        let current = Uuid::new_v4();
+        let suspect = Uuid::new_v4();
+
+        tree.insert(make_requirement(
+            parent,
+            Hrid::try_from("P-001").unwrap(),
+            vec![],
+        ));
+        tree.insert(make_requirement(
+            current,
+            Hrid::try_from("C-001").unwrap(),
+            vec![],
+        ));
+        tree.insert(make_requirement(
+            suspect,
+            Hrid::try_from("C-002").unwrap(),
+            vec![(parent, Hrid::try_from("P-001").unwrap())],
+        ));
+        tree.link(current, parent).unwrap();
+
+        let tags = BTreeSet::from(["safety".to_string()]);
+        assert_eq!(tree.set_tags(parent, tags.clone()), Some(vec![current]));
+        assert_eq!(tree.set_tags(parent, tags), Some(vec![]));
+        assert_eq!(tree.requirement(parent).unwrap().tags().len(), 1);
+    }
+
+    #[test]
+    fn replace_updates_children_index() {
+        let mut tree = Tree::default();
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+        let child = Uuid::new_v4();
+
+        tree.insert(make_requirement(
+            a,
+            Hrid::try_from("A-001").unwrap(),
+            vec![],
+        ));
+        tree.insert(make_requirement(
+            b,
+            Hrid::try_from("B-001").unwrap(),
+            vec![],
+        ));
+        tree.insert(make_requirement(
+            child,
+            Hrid::try_from("C-001").unwrap(),
+            vec![],
+        ));
+        tree.link(child, a).unwrap();
+        let grandchild = Uuid::new_v4();
+        tree.insert(make_requirement(
+            grandchild,
+            Hrid::try_from("D-001").unwrap(),
+            vec![],
+        ));
+        tree.link(grandchild, child).unwrap();
+
+        let mut edited = tree.requirement(child).unwrap().clone();
+        edited.remove_parent(a);
+        edited.add_parent(
+            b,
+            Parent {
+                hrid: Hrid::try_from("B-001").unwrap(),
+                fingerprint: tree.requirement(b).unwrap().fingerprint(),
+                relation: None,
+            },
+        );
+        edited.set_content("changed".to_string());
+
+        assert_eq!(tree.replace(edited), Ok(vec![grandchild]));
+        assert_eq!(tree.children(a).count(), 0);
+        assert_eq!(tree.children(b).collect::<Vec<_>>(), vec![child]);
+
+        let renamed = make_requirement(child, Hrid::try_from("X-001").unwrap(), vec![]);
+        assert!(matches!(
+            tree.replace(renamed),
+            Err(ReplaceError::HridChanged { .. })
+        ));
+    }
+
+    #[test]
+    fn replace_rejects_missing_parents_and_cycles() {
+        let mut tree = Tree::default();
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+        let missing = Uuid::new_v4();
+
+        tree.insert(make_requirement(
+            a,
+            Hrid::try_from("A-001").unwrap(),
+            vec![],
+        ));
+        tree.insert(make_requirement(
+            b,
+            Hrid::try_from("B-001").unwrap(),
+            vec![(missing, Hrid::try_from("GONE-001").unwrap())],
+        ));
+        tree.link(b, a).unwrap();
+
+        let link = |hrid: &str| Parent {
+            hrid: Hrid::try_from(hrid).unwrap(),
+            fingerprint: String::new(),
+            relation: None,
+        };
+
+        let mut edited = tree.requirement(a).unwrap().clone();
+        edited.add_parent(b, link("B-001"));
+        let Err(ReplaceError::Cycle(cycle)) = tree.replace(edited) else {
+            panic!("expected a cycle");
+        };
+        assert_eq!(cycle.to_string(), "A-001 -> B-001 -> A-001");
+
+        let mut edited = tree.requirement(a).unwrap().clone();
+        edited.add_parent(Uuid::new_v4(), link("X-001"));
+        assert!(matches!(
+            tree.replace(edited),
+            Err(ReplaceError::ParentNotFound { .. })
+        ));
+        assert_eq!(tree.parents(a).count(), 0);
+
+        // A link which was already dangling is kept
+        let mut edited = tree.requirement(b).unwrap().clone();
+        edited.set_content("changed".to_string());
+        assert_eq!(tree.replace(edited), Ok(vec![]));
+    }
+
+    #[test]
+    fn update_hrids_skips_broken_links() {
         let mut tree = Tree::default();
         let missing_uuid = Uuid::new_v4();
         let child_uuid = Uuid::new_v4();
         let child = make_requirement(
             child_uuid,
             Hrid::try_from("C-001").unwrap(),
-            vec![(missing_uuid, Hrid::try_from("UNKNOWN-001").unwrap())],
+            vec![
+                (missing_uuid, Hrid::try_from("UNKNOWN-001").unwrap()),
+                (child_uuid, Hrid::try_from("SELF-001").unwrap()),
+            ],
         );
 
         tree.insert(child);
-        let _ = tree.update_hrids().collect::<Vec<_>>();
+
+        assert_eq!(tree.update_hrids().count(), 0);
+    }
+
+    #[test]
+    fn broken_links_are_reported_and_pruned() {
+        let mut tree = Tree::default();
+        let missing_uuid = Uuid::new_v4();
+        let self_uuid = Uuid::new_v4();
+        let child_uuid = Uuid::new_v4();
+
+        tree.insert(make_requirement(
+            self_uuid,
+            Hrid::try_from("SELF-001").unwrap(),
+            vec![(self_uuid, Hrid::try_from("SELF-001").unwrap())],
+        ));
+        tree.insert(make_requirement(
+            child_uuid,
+            Hrid::try_from("C-001").unwrap(),
+            vec![
+                (missing_uuid, Hrid::try_from("GONE-001").unwrap()),
+                (self_uuid, Hrid::try_from("SELF-001").unwrap()),
+            ],
+        ));
+
+        let broken_links = tree.broken_links();
+        assert_eq!(
+            broken_links
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec![
+                format!("C-001 links to missing parent GONE-001 ({missing_uuid})"),
+                "SELF-001 is its own parent".to_string(),
+            ]
+        );
+        assert_eq!(broken_links[0].kind, BrokenLinkKind::Dangling);
+        assert_eq!(broken_links[1].kind, BrokenLinkKind::SelfParent);
+
+        assert_eq!(tree.prune_broken_links(), broken_links);
+        assert!(tree.broken_links().is_empty());
+        assert_eq!(
+            tree.children(self_uuid).collect::<Vec<_>>(),
+            vec![child_uuid]
+        );
+        assert_eq!(tree.children(missing_uuid).count(), 0);
+    }
+
+    #[test]
+    fn find_cycles_reports_no_cycles_in_a_dag() {
+        let mut tree = Tree::default();
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+        let c = Uuid::new_v4();
+
+        tree.insert(make_requirement(
+            a,
+            Hrid::try_from("A-001").unwrap(),
+            vec![],
+        ));
+        tree.insert(make_requirement(
+            b,
+            Hrid::try_from("B-001").unwrap(),
+            vec![(a, Hrid::try_from("A-001").unwrap())],
+        ));
+        tree.insert(make_requirement(
+            c,
+            Hrid::try_from("C-001").unwrap(),
+            vec![
+                (a, Hrid::try_from("A-001").unwrap()),
+                (b, Hrid::try_from("B-001").unwrap()),
+            ],
+        ));
+
+        assert!(tree.find_cycles().is_empty());
+    }
+
+    #[test]
+    fn find_cycles_reports_long_cycles_in_order() {
+        let mut tree = Tree::default();
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+        let c = Uuid::new_v4();
+
+        // A -> B -> C -> A
+        tree.insert(make_requirement(
+            a,
+            Hrid::try_from("A-001").unwrap(),
+            vec![(b, Hrid::try_from("B-001").unwrap())],
+        ));
+        tree.insert(make_requirement(
+            b,
+            Hrid::try_from("B-001").unwrap(),
+            vec![(c, Hrid::try_from("C-001").unwrap())],
+        ));
+        tree.insert(make_requirement(
+            c,
+            Hrid::try_from("C-001").unwrap(),
+            vec![(a, Hrid::try_from("A-001").unwrap())],
+        ));
+
+        let cycles = tree.find_cycles();
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(
+            cycles[0].hrids(),
+            &[
+                Hrid::try_from("A-001").unwrap(),
+                Hrid::try_from("B-001").unwrap(),
+                Hrid::try_from("C-001").unwrap(),
+            ]
+        );
+        assert_eq!(cycles[0].to_string(), "A-001 -> B-001 -> C-001 -> A-001");
     }
 
     #[test]
-    #[should_panic(expected = "is its own parent")]
-    fn test_update_hrids_self_parent_panics() {
+    fn find_cycles_reports_self_parents() {
         let mut tree = Tree::default();
         let uuid = Uuid::new_v4();
-        let req = make_requirement(
+        tree.insert(make_requirement(
             uuid,
             Hrid::try_from("SELF-001").unwrap(),
             vec![(uuid, Hrid::try_from("SELF-001").unwrap())],
+        ));
+
+        let cycles = tree.find_cycles();
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].hrids(), &[Hrid::try_from("SELF-001").unwrap()]);
+    }
+
+    #[test]
+    fn link_rejects_cycles() {
+        let mut tree = Tree::default();
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+
+        tree.insert(make_requirement(
+            a,
+            Hrid::try_from("A-001").unwrap(),
+            vec![],
+        ));
+        tree.insert(make_requirement(
+            b,
+            Hrid::try_from("B-001").unwrap(),
+            vec![(a, Hrid::try_from("A-001").unwrap())],
+        ));
+
+        let error = tree.link(a, b).unwrap_err();
+        assert_eq!(
+            error,
+            LinkError::Cycle(Cycle(vec![
+                Hrid::try_from("A-001").unwrap(),
+                Hrid::try_from("B-001").unwrap(),
+            ]))
         );
+        assert!(matches!(tree.link(a, a), Err(LinkError::Cycle(_))));
+        assert_eq!(tree.requirement(a).unwrap().parents().count(), 0);
+    }
 
-        tree.insert(req);
-        let _ = tree.update_hrids().collect::<Vec<_>>();
+    #[test]
+    fn suspect_links_reports_changed_parents() {
+        let mut tree = Tree::default();
+        let parent = Requirement::new(Hrid::try_from("P-001").unwrap(), "original".to_string());
+        let changed = Requirement::new(Hrid::try_from("P-001").unwrap(), "changed".to_string());
+        let child_uuid = Uuid::new_v4();
+
+        let mut child = make_requirement(child_uuid, Hrid::try_from("C-001").unwrap(), vec![]);
+        child.add_parent(
+            changed.uuid(),
+            crate::domain::requirement::Parent {
+                hrid: Hrid::try_from("P-001").unwrap(),
+                fingerprint: parent.fingerprint(),
+                relation: None,
+            },
+        );
+
+        tree.insert(changed.clone());
+        tree.insert(child);
+
+        assert_eq!(
+            tree.suspect_links(),
+            vec![SuspectLink {
+                child: child_uuid,
+                child_hrid: Hrid::try_from("C-001").unwrap(),
+                parent: changed.uuid(),
+                parent_hrid: Hrid::try_from("P-001").unwrap(),
+                stored_fingerprint: parent.fingerprint(),
+                current_fingerprint: changed.fingerprint(),
+            }]
+        );
+        assert_eq!(tree.is_suspect(child_uuid, changed.uuid()), Some(true));
+    }
+
+    #[test]
+    fn suspect_links_ignores_up_to_date_links() {
+        let mut tree = Tree::default();
+        let parent = Requirement::new(Hrid::try_from("P-001").unwrap(), "content".to_string());
+        let child = Requirement::new(Hrid::try_from("C-001").unwrap(), String::new());
+        let (parent_uuid, child_uuid) = (parent.uuid(), child.uuid());
+
+        tree.insert(parent);
+        tree.insert(child);
+        tree.link(child_uuid, parent_uuid).unwrap();
+
+        assert!(tree.suspect_links().is_empty());
+        assert_eq!(tree.is_suspect(child_uuid, parent_uuid), Some(false));
+        assert_eq!(tree.is_suspect(parent_uuid, child_uuid), None);
+    }
+
+    #[test]
+    fn review_refreshes_fingerprints() {
+        let mut tree = Tree::default();
+        let parent = Requirement::new(Hrid::try_from("P-001").unwrap(), "changed".to_string());
+        let child_uuid = Uuid::new_v4();
+        let child = make_requirement(
+            child_uuid,
+            Hrid::try_from("C-001").unwrap(),
+            vec![(parent.uuid(), Hrid::try_from("P-001").unwrap())],
+        );
+        let parent_uuid = parent.uuid();
+
+        tree.insert(parent);
+        tree.insert(child);
+        assert_eq!(tree.suspect_links().len(), 1);
+
+        assert_eq!(tree.review(child_uuid, None), vec![parent_uuid]);
+        assert!(tree.suspect_links().is_empty());
+
+        // Reviewing again is a no-op
+        assert!(tree.review(child_uuid, Some(parent_uuid)).is_empty());
+    }
+
+    #[test]
+    fn children_are_indexed_on_insert_and_link() {
+        let mut tree = Tree::default();
+        let parent = Uuid::new_v4();
+        let first = Uuid::new_v4();
+        let second = Uuid::new_v4();
+
+        // The child is inserted before its parent
+        tree.insert(make_requirement(
+            first,
+            Hrid::try_from("C-001").unwrap(),
+            vec![(parent, Hrid::try_from("P-001").unwrap())],
+        ));
+        tree.insert(make_requirement(
+            parent,
+            Hrid::try_from("P-001").unwrap(),
+            vec![],
+        ));
+        tree.insert(make_requirement(
+            second,
+            Hrid::try_from("C-002").unwrap(),
+            vec![],
+        ));
+        tree.link(second, parent).unwrap();
+
+        let children: Vec<_> = tree.children(parent).collect();
+        assert_eq!(children, vec![first, second]);
+        assert_eq!(tree.children(first).count(), 0);
+    }
+
+    #[test]
+    fn descendants_and_ancestors_visit_each_requirement_once() {
+        let mut tree = Tree::default();
+        let top = Uuid::new_v4();
+        let left = Uuid::new_v4();
+        let right = Uuid::new_v4();
+        let bottom = Uuid::new_v4();
+
+        // A diamond: bottom -> (left, right) -> top
+        tree.insert(make_requirement(
+            top,
+            Hrid::try_from("USR-001").unwrap(),
+            vec![],
+        ));
+        tree.insert(make_requirement(
+            left,
+            Hrid::try_from("SYS-001").unwrap(),
+            vec![(top, Hrid::try_from("USR-001").unwrap())],
+        ));
+        tree.insert(make_requirement(
+            right,
+            Hrid::try_from("SYS-002").unwrap(),
+            vec![(top, Hrid::try_from("USR-001").unwrap())],
+        ));
+        tree.insert(make_requirement(
+            bottom,
+            Hrid::try_from("TST-001").unwrap(),
+            vec![
+                (left, Hrid::try_from("SYS-001").unwrap()),
+                (right, Hrid::try_from("SYS-002").unwrap()),
+            ],
+        ));
+
+        let descendants: Vec<_> = tree.descendants(top).collect();
+        assert_eq!(descendants.len(), 3);
+        assert_eq!(descendants.last(), Some(&bottom));
+
+        let ancestors: Vec<_> = tree.ancestors(bottom).collect();
+        assert_eq!(ancestors.len(), 3);
+        assert_eq!(ancestors.last(), Some(&top));
+
+        assert_eq!(tree.descendants(bottom).count(), 0);
+        assert_eq!(tree.ancestors(top).count(), 0);
     }
 }